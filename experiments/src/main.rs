@@ -1,43 +1,173 @@
-use std::rc::Rc;
+mod spec;
+mod tournament;
 
+use std::{fs, path::PathBuf, process};
+
+use clap::Parser;
 use game_logic::{
-    board::{self, Board},
-    monte_carlo::types::Node,
-    types::Color,
+    game_state::GameState,
+    transcript::Transcript,
+    types::{AgentType, Color, GameStatus},
 };
-use rand::seq::IndexedRandom;
-
-fn random_move(board: &board::Board, color: Color) -> Board {
-    let mut rng = rand::rng();
-    let choosed = *board.valid_moves(color).choose(&mut rng).unwrap();
-    let mut board = *board;
-    board.move_piece(choosed, color).unwrap();
-    board
+use tournament::{Summary, Timing, play_game};
+
+/// 2 つのエージェントを対局させて強さを比べる
+#[derive(Parser)]
+struct Args {
+    /// エージェント A (random | mcts[:preset,key=value...] | alphabeta[:preset,key=value...])
+    #[arg(short = 'a', long, value_parser = spec::parse_agent)]
+    agent_a: AgentType,
+    /// エージェント B
+    #[arg(short = 'b', long, value_parser = spec::parse_agent)]
+    agent_b: AgentType,
+    /// 対局数
+    #[arg(short = 'n', long, default_value_t = 10)]
+    games: u32,
+    /// 先後を入れ替えず、常に A を黒にする
+    #[arg(long)]
+    no_alternate: bool,
+    /// 開始局面のファイル。1 行に 1 つの局面文字列か棋譜を書く
+    #[arg(long)]
+    openings: Option<PathBuf>,
+    /// 乱数のシード。指定すると i 局目はシード + i で指し、同じ結果を再現できる
+    #[arg(long)]
+    seed: Option<u64>,
 }
-fn main() {
-    let mut board = Board::new();
 
-    loop {
-        let mut moved = false;
-        if !board.valid_moves(Color::Black).is_empty() {
-            board = random_move(&board, Color::Black);
-            moved = true;
+fn load_openings(path: &PathBuf) -> Result<Vec<GameState>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    let mut openings = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
         }
-        if !board.valid_moves(Color::White).is_empty() {
-            let monte_node = Rc::new(Node::new(board, Color::White, &Color::White, None, 0));
-            for total_visits in 1..1001 {
-                let node = monte_node.select(total_visits);
-                let winner = node.simulate();
-                node.backpropagate(winner);
-            }
-            let best_move = monte_node.best_move();
-            board.move_piece(best_move, Color::White).unwrap();
-
-            moved = true;
+        // 局面文字列は手番を含めて 65 文字
+        let state = if line.chars().filter(|c| !c.is_whitespace()).count() == 65 {
+            GameState::from_position(line)
+        } else {
+            line.parse::<Transcript>().and_then(|t| t.replay())
         }
-        if !moved {
-            break;
+        .map_err(|err| format!("{}:{}: {err}", path.display(), index + 1))?;
+        if state.status != GameStatus::InProgress {
+            return Err(format!(
+                "{}:{}: the game is already over",
+                path.display(),
+                index + 1
+            ));
         }
+        openings.push(state);
+    }
+    if openings.is_empty() {
+        return Err(format!("{}: no positions", path.display()));
+    }
+    Ok(openings)
+}
+
+fn main() {
+    let args = Args::parse();
+    let openings = match &args.openings {
+        Some(path) => load_openings(path).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        }),
+        None => {
+            let mut state = GameState::new();
+            state.status = GameStatus::InProgress;
+            vec![state]
+        }
+    };
+
+    let mut summary = Summary::default();
+    let mut timings = [Timing::default(), Timing::default()];
+    for game in 0..args.games {
+        // 先後を入れ替えるときは、同じ開始局面で 2 局続けて指す
+        let (a_color, opening) = if args.no_alternate {
+            (Color::Black, game as usize)
+        } else if game % 2 == 0 {
+            (Color::Black, game as usize / 2)
+        } else {
+            (Color::White, game as usize / 2)
+        };
+        let start = &openings[opening % openings.len()];
+        let seed = args.seed.map(|seed| seed.wrapping_add(game as u64));
+        let result = play_game(
+            start,
+            args.agent_a,
+            args.agent_b,
+            a_color,
+            seed,
+            &mut timings,
+        );
+        summary.add(&result);
+        println!(
+            "game {:>4}: A as {:<5} {:+3}",
+            game + 1,
+            format!("{:?}", result.a_color),
+            result.disc_diff
+        );
+    }
+
+    println!();
+    println!(
+        "A vs B: {} wins, {} draws, {} losses ({} games)",
+        summary.wins,
+        summary.draws,
+        summary.losses,
+        summary.games()
+    );
+    println!("average disc diff: {:+.2}", summary.average_disc_diff());
+    println!("{}", summary.score_line());
+    for (name, timing) in ["A", "B"].iter().zip(&timings) {
+        println!(
+            "{name}: {} moves (+{} book moves), {:.2} ms/move on average, {:.2} ms at most",
+            timing.moves,
+            timing.book_moves,
+            timing.average().as_secs_f64() * 1000.0,
+            timing.max.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use game_logic::board::Board;
+
+    use super::*;
+
+    fn load(name: &str, text: &str) -> Result<Vec<GameState>, String> {
+        let path = std::env::temp_dir().join(format!("openings-{}-{name}", process::id()));
+        fs::write(&path, text).unwrap();
+        let result = load_openings(&path);
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn loads_positions_and_transcripts() {
+        let text = "\
+# 開始局面
+---------------------------OX------XO--------------------------- X
+f5d6c3  # 兎
+";
+        let openings = load("valid", text).unwrap();
+        assert_eq!(openings.len(), 2);
+        assert_eq!(openings[0].board, Board::new());
+        assert_eq!(openings[0].turn, Color::Black);
+        assert_eq!(openings[0].status, GameStatus::InProgress);
+        let expected = "f5d6c3".parse::<Transcript>().unwrap().replay().unwrap();
+        assert_eq!(openings[1].board, expected.board);
+        assert_eq!(openings[1].turn, Color::White);
+        assert_eq!(openings[1].status, GameStatus::InProgress);
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        let err = load("illegal", "f5\nf5f5\n").unwrap_err();
+        assert!(err.contains(":2:"), "{err}");
+        let err = load("over", &format!("{} X\n", "X".repeat(64))).unwrap_err();
+        assert!(err.contains("already over"), "{err}");
+        let err = load("empty", "# なし\n\n").unwrap_err();
+        assert!(err.contains("no positions"), "{err}");
     }
-    println!("{:?}", board.count_pieces());
 }
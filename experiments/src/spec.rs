@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use game_logic::{
+    alpha_beta_agent::AlphaBetaConfig, monte_carlo::config::SearchConfig, types::AgentType,
+};
+
+// "mcts:hard,iterations=5000" や "alphabeta:normal,book=true" のようなエージェント指定を読む
+// 先頭は種類、続く要素はプリセット名か key=value
+pub fn parse_agent(spec: &str) -> Result<AgentType, String> {
+    let (kind, options) = spec.split_once(':').unwrap_or((spec, ""));
+    let options = options.split(',').filter(|option| !option.is_empty());
+    match kind {
+        "random" => match options.clone().next() {
+            None => Ok(AgentType::Random),
+            Some(option) => Err(format!("random takes no options: {option}")),
+        },
+        "mcts" => {
+            let mut config = SearchConfig::default();
+            for option in options {
+                match option {
+                    "easy" => config = SearchConfig::easy(),
+                    "normal" => config = SearchConfig::normal(),
+                    "hard" => config = SearchConfig::hard(),
+                    _ => match key_value(option)? {
+                        ("iterations", value) => config.iterations = parse(value)?,
+                        ("time", value) => {
+                            config.time_limit = Some(Duration::from_millis(parse(value)?))
+                        }
+                        ("exploration", value) => config.exploration = parse(value)?,
+                        ("endgame", value) => config.endgame_empties = parse(value)?,
+                        ("book", value) => config.book = parse(value)?,
+                        (key, _) => return Err(format!("unknown mcts option: {key}")),
+                    },
+                }
+            }
+            Ok(AgentType::MonteCarlo(config))
+        }
+        "alphabeta" => {
+            let mut config = AlphaBetaConfig::default();
+            for option in options {
+                match option {
+                    "easy" => config = AlphaBetaConfig::easy(),
+                    "normal" => config = AlphaBetaConfig::normal(),
+                    "hard" => config = AlphaBetaConfig::hard(),
+                    _ => match key_value(option)? {
+                        ("depth", value) => config.depth = parse(value)?,
+                        ("endgame", value) => config.endgame_empties = parse(value)?,
+                        ("book", value) => config.book = parse(value)?,
+                        (key, _) => return Err(format!("unknown alphabeta option: {key}")),
+                    },
+                }
+            }
+            Ok(AgentType::AlphaBeta(config))
+        }
+        _ => Err(format!(
+            "unknown agent: {kind} (expected random, mcts or alphabeta)"
+        )),
+    }
+}
+
+fn key_value(option: &str) -> Result<(&str, &str), String> {
+    option
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=value or a preset: {option}"))
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kinds_and_presets() {
+        assert_eq!(parse_agent("random"), Ok(AgentType::Random));
+        assert_eq!(
+            parse_agent("mcts"),
+            Ok(AgentType::MonteCarlo(SearchConfig::default()))
+        );
+        assert_eq!(
+            parse_agent("mcts:hard"),
+            Ok(AgentType::MonteCarlo(SearchConfig::hard()))
+        );
+        assert_eq!(
+            parse_agent("alphabeta:easy"),
+            Ok(AgentType::AlphaBeta(AlphaBetaConfig::easy()))
+        );
+    }
+
+    #[test]
+    fn options_override_the_preset() {
+        let mut expected = SearchConfig::normal();
+        expected.iterations = 5000;
+        expected.time_limit = Some(Duration::from_millis(200));
+        expected.book = false;
+        assert_eq!(
+            parse_agent("mcts:normal,iterations=5000,time=200,book=false"),
+            Ok(AgentType::MonteCarlo(expected))
+        );
+
+        let mut expected = AlphaBetaConfig::hard();
+        expected.depth = 3;
+        expected.endgame_empties = 12;
+        expected.book = true;
+        assert_eq!(
+            parse_agent("alphabeta:hard,depth=3,endgame=12,book=true"),
+            Ok(AgentType::AlphaBeta(expected))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        for spec in [
+            "minimax",
+            "random:easy",
+            "mcts:depth=3",
+            "alphabeta:iterations=10",
+            "mcts:iterations=many",
+            "alphabeta:book=yes",
+            "alphabeta:deep",
+        ] {
+            assert!(parse_agent(spec).is_err(), "{spec}");
+        }
+    }
+}
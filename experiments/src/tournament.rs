@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use game_logic::{
+    game_controller::GameController,
+    game_state::GameState,
+    opening_book::OpeningBook,
+    types::{AgentType, Color, GameStatus, Player, Players},
+};
+
+// 1 局の結果。石差は A から見た値
+pub struct GameResult {
+    pub a_color: Color,
+    pub disc_diff: i32,
+}
+
+// エージェントごとの考慮時間。定石手はほぼ時間がかからないので別に数える
+#[derive(Default)]
+pub struct Timing {
+    pub moves: u32,
+    pub book_moves: u32,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    fn record(&mut self, elapsed: Duration) {
+        self.moves += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    fn record_book(&mut self) {
+        self.book_moves += 1;
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.moves == 0 {
+            return Duration::ZERO;
+        }
+        self.total / self.moves
+    }
+}
+
+fn uses_book(agent: AgentType) -> bool {
+    match agent {
+        AgentType::Random => false,
+        AgentType::MonteCarlo(config) => config.book,
+        AgentType::AlphaBeta(config) => config.book,
+    }
+}
+
+// start から A と B を対局させる
+pub fn play_game(
+    start: &GameState,
+    a: AgentType,
+    b: AgentType,
+    a_color: Color,
+    seed: Option<u64>,
+    timings: &mut [Timing; 2],
+) -> GameResult {
+    let mut state = start.clone();
+    state.players = match a_color {
+        Color::Black => Players {
+            black: Player::Agent(a),
+            white: Player::Agent(b),
+        },
+        Color::White => Players {
+            black: Player::Agent(b),
+            white: Player::Agent(a),
+        },
+    };
+    let book = OpeningBook::standard();
+    let mut controller = GameController::with_seed(state, seed);
+    while controller.state.status == GameStatus::InProgress {
+        let (index, agent) = if controller.state.turn == a_color {
+            (0, a)
+        } else {
+            (1, b)
+        };
+        let in_book = uses_book(agent)
+            && !book
+                .lookup(&controller.state.board, controller.state.turn)
+                .is_empty();
+        let start = Instant::now();
+        controller
+            .step()
+            .expect("both players are agents, so every turn is an agent move");
+        if in_book {
+            timings[index].record_book();
+        } else {
+            timings[index].record(start.elapsed());
+        }
+    }
+
+    let (black_count, white_count) = controller.state.board.count_pieces();
+    let black_diff = black_count as i32 - white_count as i32;
+    GameResult {
+        a_color,
+        disc_diff: match a_color {
+            Color::Black => black_diff,
+            Color::White => -black_diff,
+        },
+    }
+}
+
+// A から見た勝敗の集計
+#[derive(Default)]
+pub struct Summary {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub disc_diff_total: i64,
+}
+
+impl Summary {
+    pub fn add(&mut self, result: &GameResult) {
+        match result.disc_diff.signum() {
+            1 => self.wins += 1,
+            0 => self.draws += 1,
+            _ => self.losses += 1,
+        }
+        self.disc_diff_total += result.disc_diff as i64;
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn average_disc_diff(&self) -> f64 {
+        self.disc_diff_total as f64 / self.games().max(1) as f64
+    }
+
+    // 引き分けを 0.5 勝とした得点率
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games().max(1) as f64
+    }
+
+    // Elo 差と、得点率の正規近似による 95% 信頼区間
+    pub fn elo(&self) -> (f64, f64, f64) {
+        let games = self.games().max(1) as f64;
+        let score = self.score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+        let margin = 1.96 * (variance / games).sqrt();
+        (
+            elo_from_score(score),
+            elo_from_score(score - margin),
+            elo_from_score(score + margin),
+        )
+    }
+
+    // 得点率が 0% か 100% なら Elo 差は ±inf になる
+    pub fn score_line(&self) -> String {
+        let (elo, low, high) = self.elo();
+        format!(
+            "score: {:.1}%, Elo: {:+.1} (95% CI {:+.1} .. {:+.1})",
+            self.score() * 100.0,
+            elo,
+            low,
+            high
+        )
+    }
+}
+
+fn elo_from_score(score: f64) -> f64 {
+    if score <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if score >= 1.0 {
+        return f64::INFINITY;
+    }
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(wins: u32, draws: u32, losses: u32) -> Summary {
+        Summary {
+            wins,
+            draws,
+            losses,
+            disc_diff_total: 0,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn elo_from_known_scores() {
+        assert_close(elo_from_score(0.5), 0.0);
+        assert_close(elo_from_score(0.75), 190.849);
+        assert_close(elo_from_score(0.25), -190.849);
+    }
+
+    #[test]
+    fn confidence_interval_from_known_results() {
+        let (elo, low, high) = summary(6, 2, 2).elo();
+        assert_close(elo, 147.191);
+        assert_close(low, -33.403);
+        assert_close(high, 504.049);
+
+        // 上限の得点率が 1 を超えると +inf
+        let (elo, low, high) = summary(3, 0, 1).elo();
+        assert_close(elo, 190.849);
+        assert_close(low, -126.456);
+        assert_eq!(high, f64::INFINITY);
+
+        let (elo, _, _) = summary(4, 2, 4).elo();
+        assert_close(elo, 0.0);
+    }
+
+    #[test]
+    fn perfect_scores_print_infinity() {
+        assert_eq!(
+            summary(0, 0, 5).score_line(),
+            "score: 0.0%, Elo: -inf (95% CI -inf .. -inf)"
+        );
+        assert_eq!(
+            summary(5, 0, 0).score_line(),
+            "score: 100.0%, Elo: +inf (95% CI +inf .. +inf)"
+        );
+    }
+}
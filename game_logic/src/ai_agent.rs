@@ -1,7 +1,12 @@
 use std::fmt::Debug;
 
 use crate::{
+    alpha_beta_agent::AlphaBetaAgent,
+    book_agent::BookAgent,
+    endgame_agent::EndgameAgent,
     monte_carlo_agent::MonteCarloAgent,
+    opening_book::OpeningBook,
+    rng::{AgentRng, seeded_rng, split_rng},
     types::{AgentType, Color, Coordinate},
 };
 
@@ -10,23 +15,59 @@ use super::{board::Board, random_agent::RandomAgent};
 pub trait AiAgent: Debug {
     fn color(&self) -> Color;
     fn agent_type(&self) -> AgentType;
+    // 自分に合法手がある局面でだけ呼ぶ。パスは呼び出し側 (GameState) が処理する
     fn next_move(&self, board: &Board) -> Coordinate;
+    fn clone_box(&self) -> Box<dyn AiAgent>;
 }
 
-pub fn initialize_agent(agent_type: AgentType, color: Color) -> Box<dyn AiAgent> {
+// seed を与えると、同じ局面の列に対して毎回同じ手を返す
+pub fn initialize_agent(
+    agent_type: AgentType,
+    color: Color,
+    seed: Option<u64>,
+) -> Box<dyn AiAgent> {
+    let mut rng = seeded_rng(seed);
     match agent_type {
-        AgentType::Random => Box::new(RandomAgent::new(color)),
-        AgentType::MonteCarlo => Box::new(MonteCarloAgent::new(color)),
+        AgentType::Random => Box::new(RandomAgent::new(color, split_rng(&mut rng))),
+        AgentType::MonteCarlo(config) => {
+            let agent = MonteCarloAgent::new(color, config, split_rng(&mut rng));
+            with_book(
+                with_endgame(Box::new(agent), config.endgame_empties),
+                config.book,
+                &mut rng,
+            )
+        }
+        AgentType::AlphaBeta(config) => with_book(
+            with_endgame(
+                Box::new(AlphaBetaAgent::new(color, config)),
+                config.endgame_empties,
+            ),
+            config.book,
+            &mut rng,
+        ),
     }
 }
 
-pub fn renew_agent(agent: &Option<Box<dyn AiAgent>>) -> Option<Box<dyn AiAgent>> {
-    if let Some(agent) = agent {
-        match agent.agent_type() {
-            AgentType::Random => Some(Box::new(RandomAgent::new(agent.color()))),
-            AgentType::MonteCarlo => Some(Box::new(MonteCarloAgent::new(agent.color()))),
-        }
+fn with_book(agent: Box<dyn AiAgent>, book: bool, rng: &mut AgentRng) -> Box<dyn AiAgent> {
+    if !book {
+        return agent;
+    }
+    Box::new(BookAgent::new(
+        agent,
+        OpeningBook::standard(),
+        split_rng(rng),
+    ))
+}
+
+fn with_endgame(agent: Box<dyn AiAgent>, empties: u32) -> Box<dyn AiAgent> {
+    if empties == 0 {
+        agent
     } else {
-        None
+        Box::new(EndgameAgent::new(agent, empties))
     }
 }
+
+// 探索木などの内部状態を引き継いだエージェントを返す
+pub fn renew_agent(agent: &Option<Box<dyn AiAgent>>) -> Option<Box<dyn AiAgent>> {
+    agent.as_ref().map(|agent| agent.clone_box())
+}
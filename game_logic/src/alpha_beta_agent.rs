@@ -0,0 +1,265 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    evaluation::{evaluate, ordered_moves},
+    transposition::TranspositionTable,
+};
+
+use super::{
+    ai_agent::AiAgent,
+    board::Board,
+    types::{AgentType, Color, Coordinate},
+};
+
+// 終局時の評価値。石差を足して、どの静的評価よりも優先させる
+const WIN_SCORE: i32 = 100_000;
+// 置換表のエントリ数は 2^TABLE_BITS
+const TABLE_BITS: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bound {
+    Exact,
+    // 真の値はこれ以上
+    Lower,
+    // 真の値はこれ以下
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    depth: u32,
+    score: i32,
+    bound: Bound,
+    best_move: Option<Coordinate>,
+}
+
+type Table = TranspositionTable<Entry>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlphaBetaConfig {
+    pub depth: u32,
+    // 空きマスがこの数以下になったら完全読みに切り替える (0 なら切り替えない)
+    pub endgame_empties: u32,
+    // 定石を使うか。定石手はランダムに選ぶので、使うと同じ局面でも手が変わる
+    pub book: bool,
+}
+
+impl Default for AlphaBetaConfig {
+    fn default() -> Self {
+        AlphaBetaConfig {
+            depth: 5,
+            endgame_empties: 12,
+            book: false,
+        }
+    }
+}
+
+impl AlphaBetaConfig {
+    pub fn easy() -> Self {
+        AlphaBetaConfig {
+            depth: 2,
+            endgame_empties: 0,
+            ..Default::default()
+        }
+    }
+
+    pub fn normal() -> Self {
+        Self::default()
+    }
+
+    pub fn hard() -> Self {
+        AlphaBetaConfig {
+            depth: 7,
+            endgame_empties: 16,
+            ..Default::default()
+        }
+    }
+}
+
+// 置換表は着手をまたいで使い回す
+#[derive(Debug, Clone)]
+pub struct AlphaBetaAgent {
+    color: Color,
+    config: AlphaBetaConfig,
+    table: Rc<RefCell<Table>>,
+}
+
+impl AlphaBetaAgent {
+    pub fn new(color: Color, config: AlphaBetaConfig) -> Self {
+        AlphaBetaAgent {
+            color,
+            config,
+            table: Rc::new(RefCell::new(TranspositionTable::new(TABLE_BITS))),
+        }
+    }
+}
+
+impl AiAgent for AlphaBetaAgent {
+    fn color(&self) -> Color {
+        self.color
+    }
+
+    fn next_move(&self, board: &Board) -> Coordinate {
+        debug_assert!(
+            board.legal_moves(self.color) != 0,
+            "{:?} has no legal moves",
+            self.color
+        );
+        let mut board = *board;
+        let mut table = self.table.borrow_mut();
+        let mut alpha = -i32::MAX;
+        let mut best_move = None;
+        for (coord, flips) in ordered_moves(&board, self.color) {
+            board.make_move(coord, self.color, flips);
+            let score = -negamax(
+                &mut table,
+                &mut board,
+                self.color.opponent(),
+                self.config.depth.saturating_sub(1),
+                -i32::MAX,
+                -alpha,
+            );
+            board.unmake_move(coord, self.color, flips);
+            if best_move.is_none() || score > alpha {
+                alpha = score;
+                best_move = Some(coord);
+            }
+        }
+        best_move.expect("next_move is only called with a legal move")
+    }
+
+    fn agent_type(&self) -> AgentType {
+        AgentType::AlphaBeta(self.config)
+    }
+
+    fn clone_box(&self) -> Box<dyn AiAgent> {
+        Box::new(self.clone())
+    }
+}
+
+fn negamax(
+    table: &mut Table,
+    board: &mut Board,
+    turn: Color,
+    depth: u32,
+    mut alpha: i32,
+    mut beta: i32,
+) -> i32 {
+    if board.legal_moves(turn) == 0 {
+        if board.legal_moves(turn.opponent()) == 0 {
+            return final_score(board, turn);
+        }
+        // パス
+        return -negamax(table, board, turn.opponent(), depth, -beta, -alpha);
+    }
+    if depth == 0 {
+        return evaluate(board, turn);
+    }
+
+    let key = board.hash_with_turn(turn);
+    let entry = table.get(key);
+    if let Some(entry) = entry
+        && entry.depth >= depth
+    {
+        match entry.bound {
+            Bound::Exact => return entry.score,
+            Bound::Lower => alpha = alpha.max(entry.score),
+            Bound::Upper => beta = beta.min(entry.score),
+        }
+        if alpha >= beta {
+            return entry.score;
+        }
+    }
+
+    let original_alpha = alpha;
+    let mut moves = ordered_moves(board, turn);
+    // 前回の最善手から読む
+    if let Some(best_move) = entry.and_then(|entry| entry.best_move)
+        && let Some(index) = moves.iter().position(|&(coord, _)| coord == best_move)
+    {
+        moves[..=index].rotate_right(1);
+    }
+
+    let mut best = -i32::MAX;
+    let mut best_move = None;
+    for (coord, flips) in moves {
+        board.make_move(coord, turn, flips);
+        let score = -negamax(table, board, turn.opponent(), depth - 1, -beta, -alpha);
+        board.unmake_move(coord, turn, flips);
+        if score > best {
+            best = score;
+            best_move = Some(coord);
+        }
+        if best > alpha {
+            alpha = best;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    let bound = if best <= original_alpha {
+        Bound::Upper
+    } else if best >= beta {
+        Bound::Lower
+    } else {
+        Bound::Exact
+    };
+    table.insert(
+        key,
+        Entry {
+            depth,
+            score: best,
+            bound,
+            best_move,
+        },
+    );
+    best
+}
+
+fn final_score(board: &Board, turn: Color) -> i32 {
+    let (own, opponent) = board.own_and_opponent(turn);
+    let diff = own.count_ones() as i32 - opponent.count_ones() as i32;
+    match diff.signum() {
+        0 => 0,
+        sign => sign * WIN_SCORE + diff,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai_agent::initialize_agent, transcript::Transcript};
+
+    fn after(moves: &str) -> Board {
+        moves.parse::<Transcript>().unwrap().replay().unwrap().board
+    }
+
+    #[test]
+    fn same_position_gives_same_move() {
+        let board = after("f5d6c3d3c4f4f6f3e6e7");
+        let moves: Vec<_> = (0..3)
+            .map(|_| AlphaBetaAgent::new(Color::Black, AlphaBetaConfig::normal()).next_move(&board))
+            .collect();
+        assert_eq!(moves, [moves[0]; 3]);
+        assert_eq!(moves[0].to_string(), "f7");
+    }
+
+    // 定石を使わなければ、シードによらず同じ手を指す
+    #[test]
+    fn seed_does_not_change_moves_without_book() {
+        let board = Board::new();
+        let moves: Vec<_> = (0..5)
+            .map(|seed| {
+                initialize_agent(
+                    AgentType::AlphaBeta(AlphaBetaConfig::easy()),
+                    Color::Black,
+                    Some(seed),
+                )
+                .next_move(&board)
+            })
+            .collect();
+        assert!(moves.iter().all(|&coord| coord == moves[0]));
+    }
+}
@@ -1,16 +1,56 @@
-use std::fmt::{self};
+use std::{
+    fmt::{self},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
 
-use super::types::{BitBoard, Color, Coordinate};
+use super::{
+    error::{GameError, NotationError},
+    types::{BitBoard, Color, Coordinate},
+    zobrist,
+};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+// 各方向のシフト量と、端の列・行をまたいで回り込まないためのマスク
+const DIRECTIONS: [(i32, BitBoard); 8] = [
+    (1, 0x7e7e_7e7e_7e7e_7e7e),
+    (-1, 0x7e7e_7e7e_7e7e_7e7e),
+    (8, 0x00ff_ffff_ffff_ff00),
+    (-8, 0x00ff_ffff_ffff_ff00),
+    (7, 0x007e_7e7e_7e7e_7e00),
+    (-7, 0x007e_7e7e_7e7e_7e00),
+    (9, 0x007e_7e7e_7e7e_7e00),
+    (-9, 0x007e_7e7e_7e7e_7e00),
+];
+
+pub(crate) fn shift_bits(bits: BitBoard, shift: i32) -> BitBoard {
+    if shift > 0 {
+        bits << shift
+    } else {
+        bits >> -shift
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Board {
     white: BitBoard,
     black: BitBoard,
+    // 石の配置の Zobrist ハッシュ。着手のたびに差分で更新する
+    hash: u64,
+}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        let mut board = Board { white: 0, black: 0 };
+        let mut board = Board {
+            white: 0,
+            black: 0,
+            hash: 0,
+        };
         // 初期配置
         board.set_piece((3, 3).into(), Color::White);
         board.set_piece((3, 4).into(), Color::Black);
@@ -25,7 +65,47 @@ impl Board {
         Self::default()
     }
 
+    // 黒と白のビットボードから盤面を作る。同じマスに両方の石があればエラー
+    pub fn from_bitboards(black: BitBoard, white: BitBoard) -> Result<Self, NotationError> {
+        if black & white != 0 {
+            return Err(NotationError::InvalidPosition(format!(
+                "{black:016x} {white:016x}"
+            )));
+        }
+        Ok(Board::from_bits(black, white))
+    }
+
+    // 重なりのないビットボードから作る
+    pub(crate) fn from_bits(black: BitBoard, white: BitBoard) -> Self {
+        Board {
+            white,
+            black,
+            hash: zobrist::hash(black, white),
+        }
+    }
+
+    pub fn black(&self) -> BitBoard {
+        self.black
+    }
+
+    pub fn white(&self) -> BitBoard {
+        self.white
+    }
+
+    // 石の配置だけのハッシュ。手番も区別するなら hash_with_turn を使う
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn hash_with_turn(&self, turn: Color) -> u64 {
+        match turn {
+            Color::Black => self.hash,
+            Color::White => self.hash ^ zobrist::WHITE_TO_MOVE,
+        }
+    }
+
     fn set_piece(&mut self, coordinate: Coordinate, color: Color) {
+        self.hash ^= zobrist::piece_key(coordinate.y * 8 + coordinate.x, color);
         match color {
             Color::Black => self.black |= coordinate.to_bit(),
             Color::White => self.white |= coordinate.to_bit(),
@@ -60,149 +140,177 @@ impl Board {
         result
     }
 
-    pub fn move_piece(&mut self, coordinate: Coordinate, turn: Color) -> Result<(), String> {
-        let bit = coordinate.to_bit();
+    // 着手して反転した石のマスクを返す
+    pub fn move_piece(
+        &mut self,
+        coordinate: Coordinate,
+        turn: Color,
+    ) -> Result<BitBoard, GameError> {
+        if coordinate.x >= 8 || coordinate.y >= 8 {
+            return Err(GameError::OutOfBounds(coordinate));
+        }
+        if self.get_piece(&coordinate).is_some() {
+            return Err(GameError::OccupiedSquare(coordinate));
+        }
+        let flips = self.flips(coordinate, turn);
+        if flips == 0 {
+            return Err(GameError::NoFlips(coordinate));
+        }
+        self.make_move(coordinate, turn, flips);
+        Ok(flips)
+    }
 
-        let (own, opponent) = match turn {
-            Color::Black => (&mut self.black, &mut self.white),
-            Color::White => (&mut self.white, &mut self.black),
-        };
+    pub fn make_move(&mut self, coordinate: Coordinate, turn: Color, flips: BitBoard) {
+        self.update_hash(coordinate, turn, flips);
+        let (own, opponent) = self.own_and_opponent_mut(turn);
+        *own |= flips | coordinate.to_bit();
+        *opponent &= !flips;
+    }
 
-        let directions = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
-
-        let mut flipped = false;
-
-        for &(dx, dy) in &directions {
-            let mut cx = coordinate.x as i32;
-            let mut cy = coordinate.y as i32;
-            let mut to_flip = Vec::new();
-
-            loop {
-                cx += dx;
-                cy += dy;
-
-                // ボードの外に出たら終了
-                if !(0..8).contains(&cx) || !(0..8).contains(&cy) {
-                    break;
-                }
+    // make_move の逆操作
+    pub fn unmake_move(&mut self, coordinate: Coordinate, turn: Color, flips: BitBoard) {
+        self.update_hash(coordinate, turn, flips);
+        let (own, opponent) = self.own_and_opponent_mut(turn);
+        *own &= !(flips | coordinate.to_bit());
+        *opponent |= flips;
+    }
 
-                let next_bit = 1 << (cy * 8 + cx);
-
-                if *opponent & next_bit != 0 {
-                    // 相手の駒を記録
-                    to_flip.push(next_bit);
-                } else if *own & next_bit != 0 {
-                    // 自分の駒が見つかれば反転可能
-                    if !to_flip.is_empty() {
-                        flipped = true;
-                        for bit in to_flip {
-                            *own |= bit;
-                            *opponent &= !bit;
-                        }
-                    }
-                    break;
-                } else {
-                    // 空白に到達したら終了
-                    break;
-                }
-            }
-        }
+    // 着手とその取り消しはどちらも同じ値の XOR になる
+    fn update_hash(&mut self, coordinate: Coordinate, turn: Color, flips: BitBoard) {
+        self.hash ^=
+            zobrist::piece_key(coordinate.y * 8 + coordinate.x, turn) ^ zobrist::flip_key(flips);
+    }
 
-        if flipped {
-            // 駒を配置
-            *own |= bit;
-            Ok(())
-        } else {
-            Err("Invalid move: No pieces to flip".into())
+    fn own_and_opponent_mut(&mut self, turn: Color) -> (&mut BitBoard, &mut BitBoard) {
+        match turn {
+            Color::Black => (&mut self.black, &mut self.white),
+            Color::White => (&mut self.white, &mut self.black),
         }
     }
 
-    pub fn valid_moves(&self, turn: Color) -> Vec<Coordinate> {
-        let mut moves = Vec::new();
-        let (own, opponent) = match turn {
+    pub fn own_and_opponent(&self, turn: Color) -> (BitBoard, BitBoard) {
+        match turn {
             Color::Black => (self.black, self.white),
             Color::White => (self.white, self.black),
-        };
+        }
+    }
 
-        // 各方向への探索オフセット
-        let directions = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
+    // coordinate に turn の石を置いたときに反転する石のマスク (置けない場合は 0)
+    pub fn flips(&self, coordinate: Coordinate, turn: Color) -> BitBoard {
+        let bit = coordinate.to_bit();
+        let (own, opponent) = self.own_and_opponent(turn);
+        if (own | opponent) & bit != 0 {
+            return 0;
+        }
 
-        for y in 0..8 {
-            for x in 0..8 {
-                let coord: Coordinate = (x, y).into();
-                let bit = coord.to_bit();
+        let mut flips = 0;
+        for &(shift, mask) in &DIRECTIONS {
+            let opponent = opponent & mask;
+            let mut line = 0;
+            let mut cursor = shift_bits(bit, shift);
+            while cursor & opponent != 0 {
+                line |= cursor;
+                cursor = shift_bits(cursor, shift);
+            }
+            // 自分の駒で挟めていれば反転可能
+            if cursor & own != 0 {
+                flips |= line;
+            }
+        }
+        flips
+    }
 
-                if (self.white | self.black) & bit != 0 {
-                    continue;
-                }
+    // turn の合法手を BitBoard で返す
+    pub fn legal_moves(&self, turn: Color) -> BitBoard {
+        let (own, opponent) = self.own_and_opponent(turn);
+        let empty = !(own | opponent);
 
-                let mut valid = false;
-
-                for &(dx, dy) in &directions {
-                    let mut found_opponent = false;
-                    let mut cx = x as i32;
-                    let mut cy = y as i32;
-
-                    loop {
-                        cx += dx;
-                        cy += dy;
-
-                        // ボードの外に出たら終了
-                        if !(0..8).contains(&cx) || !(0..8).contains(&cy) {
-                            break;
-                        }
-
-                        let next_bit = Coordinate {
-                            x: cx as u32,
-                            y: cy as u32,
-                        }
-                        .to_bit();
-
-                        if opponent & next_bit != 0 {
-                            // 相手の駒を見つけた
-                            found_opponent = true;
-                        } else if own & next_bit != 0 {
-                            // 自分の駒を見つけた
-                            if found_opponent {
-                                valid = true;
-                            }
-                            break;
-                        } else {
-                            // 空白マスに到達
-                            break;
-                        }
-                    }
-                    if valid {
-                        moves.push(coord);
-                        break;
-                    }
-                }
+        let mut moves = 0;
+        for &(shift, mask) in &DIRECTIONS {
+            let opponent = opponent & mask;
+            // 自分の駒から連続する相手の駒 (最大 6 個) をたどる
+            let mut line = shift_bits(own, shift) & opponent;
+            for _ in 0..5 {
+                line |= shift_bits(line, shift) & opponent;
             }
+            moves |= shift_bits(line, shift) & empty;
         }
         moves
     }
 
+    pub fn valid_moves(&self, turn: Color) -> Vec<Coordinate> {
+        let mut moves = self.legal_moves(turn);
+        let mut result = Vec::with_capacity(moves.count_ones() as usize);
+        while moves != 0 {
+            result.push(Coordinate::from_index(moves.trailing_zeros()));
+            moves &= moves - 1;
+        }
+        result
+    }
+
+    pub fn count_empties(&self) -> u32 {
+        64 - (self.black | self.white).count_ones()
+    }
+
     pub fn count_pieces(&self) -> (u32, u32) {
         (self.black.count_ones(), self.white.count_ones())
     }
+
+    // a1, b1, ..., h8 の順に X (黒) / O (白) / - (空) を並べ、空白と手番を続ける
+    pub fn to_position_string(&self, turn: Color) -> String {
+        let mut result: String = (0..64)
+            .map(
+                |index| match self.get_piece(&Coordinate::from_index(index)) {
+                    Some(Color::Black) => 'X',
+                    Some(Color::White) => 'O',
+                    None => '-',
+                },
+            )
+            .collect();
+        result.push(' ');
+        result.push(match turn {
+            Color::Black => 'X',
+            Color::White => 'O',
+        });
+        result
+    }
+
+    pub fn from_position_string(s: &str) -> Result<(Self, Color), NotationError> {
+        let invalid = || NotationError::InvalidPosition(s.to_string());
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.len() != 65 {
+            return Err(invalid());
+        }
+
+        let mut board = Board {
+            white: 0,
+            black: 0,
+            hash: 0,
+        };
+        for (index, &c) in chars[..64].iter().enumerate() {
+            let coordinate = Coordinate::from_index(index as u32);
+            match c {
+                'X' | 'x' | '*' => board.set_piece(coordinate, Color::Black),
+                'O' | 'o' => board.set_piece(coordinate, Color::White),
+                '-' | '.' => {}
+                _ => return Err(invalid()),
+            }
+        }
+        let turn = match chars[64] {
+            'X' | 'x' | '*' | 'B' | 'b' => Color::Black,
+            'O' | 'o' | 'W' | 'w' => Color::White,
+            _ => return Err(invalid()),
+        };
+        Ok((board, turn))
+    }
+}
+
+impl FromStr for Board {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::from_position_string(s).map(|(board, _)| board)
+    }
 }
 
 impl fmt::Display for Board {
@@ -222,3 +330,190 @@ impl fmt::Display for Board {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // depth 手先までの末端局面の数。パスも 1 手と数え、終局した局面はそこで 1 つと数える
+    fn perft(board: &Board, turn: Color, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = board.valid_moves(turn);
+        if moves.is_empty() {
+            if board.legal_moves(turn.opponent()) == 0 {
+                return 1;
+            }
+            return perft(board, turn.opponent(), depth - 1);
+        }
+        moves
+            .into_iter()
+            .map(|coord| {
+                let mut next = *board;
+                next.move_piece(coord, turn).unwrap();
+                perft(&next, turn.opponent(), depth - 1)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn perft_from_initial_position() {
+        let expected = [1, 4, 12, 56, 244, 1396, 8200, 55092, 390216];
+        for (depth, &count) in expected.iter().enumerate() {
+            assert_eq!(perft(&Board::new(), Color::Black, depth as u32), count);
+        }
+    }
+
+    // 各方向のシフトに対応する (dx, dy)
+    fn step(shift: i32) -> (i32, i32) {
+        match shift {
+            1 => (1, 0),
+            -1 => (-1, 0),
+            8 => (0, 1),
+            -8 => (0, -1),
+            7 => (-1, 1),
+            -7 => (1, -1),
+            9 => (1, 1),
+            -9 => (-1, -1),
+            _ => unreachable!(),
+        }
+    }
+
+    // 自分、相手、空きマスをシフト方向に並べ、盤の端で回り込むときだけ打てないことを確かめる
+    #[test]
+    fn moves_do_not_wrap_around_edges() {
+        for &(shift, _) in &DIRECTIONS {
+            let (dx, dy) = step(shift);
+            for index in 0..64 {
+                let (opponent, target) = (index + shift, index + 2 * shift);
+                if !(0..64).contains(&opponent) || !(0..64).contains(&target) {
+                    continue;
+                }
+                let (x, y) = (index % 8, index / 8);
+                let on_board =
+                    |k: i32| (0..8).contains(&(x + k * dx)) && (0..8).contains(&(y + k * dy));
+                let expected = on_board(1) && on_board(2);
+
+                let board = Board::from_bitboards(1 << index, 1 << opponent).unwrap();
+                let coord = Coordinate::from_index(target as u32);
+                assert_eq!(
+                    board.legal_moves(Color::Black) & coord.to_bit() != 0,
+                    expected,
+                    "shift {shift}, {}",
+                    Coordinate::from_index(index as u32)
+                );
+                assert_eq!(
+                    board.flips(coord, Color::Black) != 0,
+                    expected,
+                    "shift {shift}, {}",
+                    Coordinate::from_index(index as u32)
+                );
+            }
+        }
+    }
+
+    fn assert_hash_matches(board: &Board) {
+        let rebuilt = Board::from_bitboards(board.black(), board.white()).unwrap();
+        assert_eq!(board.hash(), rebuilt.hash(), "{board}");
+    }
+
+    // 着手と取り消しのたびに、差分更新したハッシュが作り直したものと一致するか調べる
+    fn walk(board: &mut Board, turn: Color, depth: u32) {
+        if depth == 0 {
+            return;
+        }
+        for coord in board.valid_moves(turn) {
+            let before = *board;
+            let flips = board.flips(coord, turn);
+            board.make_move(coord, turn, flips);
+            assert_hash_matches(board);
+            walk(board, turn.opponent(), depth - 1);
+            board.unmake_move(coord, turn, flips);
+            assert_hash_matches(board);
+            assert_eq!(*board, before);
+        }
+    }
+
+    #[test]
+    fn incremental_hash_matches_full_hash() {
+        let mut board = Board::new();
+        assert_hash_matches(&board);
+        walk(&mut board, Color::Black, 5);
+    }
+
+    #[test]
+    fn hash_with_turn_distinguishes_turns() {
+        let board = Board::new();
+        assert_ne!(
+            board.hash_with_turn(Color::Black),
+            board.hash_with_turn(Color::White)
+        );
+    }
+
+    const INITIAL: &str = "---------------------------OX------XO--------------------------- X";
+
+    #[test]
+    fn position_string_round_trips() {
+        assert_eq!(Board::new().to_position_string(Color::Black), INITIAL);
+        assert_eq!(
+            Board::from_position_string(INITIAL),
+            Ok((Board::new(), Color::Black))
+        );
+
+        let mut board = Board::new();
+        for (coord, turn) in [
+            ("f5", Color::Black),
+            ("d6", Color::White),
+            ("c3", Color::Black),
+        ] {
+            board.move_piece(coord.parse().unwrap(), turn).unwrap();
+        }
+        for turn in [Color::Black, Color::White] {
+            let position = board.to_position_string(turn);
+            assert_eq!(Board::from_position_string(&position), Ok((board, turn)));
+            assert_eq!(position.parse::<Board>(), Ok(board));
+        }
+    }
+
+    #[test]
+    fn position_string_accepts_alternative_symbols() {
+        let alternative = INITIAL[..64]
+            .replace('-', ".")
+            .replace('X', "*")
+            .replace('O', "o");
+        assert_eq!(
+            Board::from_position_string(&format!("{alternative} b")),
+            Ok((Board::new(), Color::Black))
+        );
+        // 空白は無視する
+        let spaced: String = INITIAL.chars().flat_map(|c| [c, ' ']).collect();
+        assert_eq!(
+            Board::from_position_string(&spaced),
+            Ok((Board::new(), Color::Black))
+        );
+        assert_eq!(
+            Board::from_position_string(&format!("{} w", &INITIAL[..64])),
+            Ok((Board::new(), Color::White))
+        );
+    }
+
+    #[test]
+    fn position_string_rejects_malformed_input() {
+        let body = &INITIAL[..64];
+        for position in [
+            String::new(),
+            body.to_string(),
+            format!("{body} XX"),
+            format!("{} X", &body[..63]),
+            format!("{}Z X", &body[..63]),
+            format!("{body} Q"),
+        ] {
+            assert_eq!(
+                Board::from_position_string(&position),
+                Err(NotationError::InvalidPosition(position.clone())),
+                "{position:?}"
+            );
+        }
+    }
+}
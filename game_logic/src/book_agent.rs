@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use crate::{opening_book::OpeningBook, rng::SharedRng};
+
+use super::{
+    ai_agent::AiAgent,
+    board::Board,
+    types::{AgentType, Color, Coordinate},
+};
+
+// 定石にある局面では定石手を指し、外れたら内側のエージェントに任せる
+#[derive(Debug)]
+pub struct BookAgent {
+    inner: Box<dyn AiAgent>,
+    book: Arc<OpeningBook>,
+    rng: SharedRng,
+}
+
+impl BookAgent {
+    pub fn new(inner: Box<dyn AiAgent>, book: Arc<OpeningBook>, rng: SharedRng) -> Self {
+        BookAgent { inner, book, rng }
+    }
+}
+
+impl AiAgent for BookAgent {
+    fn color(&self) -> Color {
+        self.inner.color()
+    }
+
+    fn next_move(&self, board: &Board) -> Coordinate {
+        match self
+            .book
+            .choose(board, self.color(), &mut *self.rng.borrow_mut())
+        {
+            Some(coord) => coord,
+            None => self.inner.next_move(board),
+        }
+    }
+
+    fn agent_type(&self) -> AgentType {
+        self.inner.agent_type()
+    }
+
+    fn clone_box(&self) -> Box<dyn AiAgent> {
+        Box::new(BookAgent::new(
+            self.inner.clone_box(),
+            self.book.clone(),
+            self.rng.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        rng::{seeded_rng, split_rng},
+        transcript::Transcript,
+    };
+
+    // 呼ばれた回数を数え、最後の合法手を返す
+    #[derive(Debug, Clone)]
+    struct LastMoveAgent {
+        color: Color,
+        calls: Rc<Cell<u32>>,
+    }
+
+    impl AiAgent for LastMoveAgent {
+        fn color(&self) -> Color {
+            self.color
+        }
+
+        fn agent_type(&self) -> AgentType {
+            AgentType::Random
+        }
+
+        fn next_move(&self, board: &Board) -> Coordinate {
+            self.calls.set(self.calls.get() + 1);
+            *board.valid_moves(self.color).last().unwrap()
+        }
+
+        fn clone_box(&self) -> Box<dyn AiAgent> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn board_after(moves: &str) -> Board {
+        moves.parse::<Transcript>().unwrap().replay().unwrap().board
+    }
+
+    #[test]
+    fn plays_book_moves_then_falls_back() {
+        let calls = Rc::new(Cell::new(0));
+        let inner = LastMoveAgent {
+            color: Color::Black,
+            calls: calls.clone(),
+        };
+        let book = OpeningBook::parse("f5d6c3 0").unwrap();
+        let agent = BookAgent::new(
+            Box::new(inner.clone()),
+            Arc::new(book),
+            split_rng(&mut seeded_rng(Some(0))),
+        );
+
+        assert_eq!(agent.next_move(&board_after("")).to_string(), "f5");
+        assert_eq!(agent.next_move(&board_after("f5d6")).to_string(), "c3");
+        assert_eq!(calls.get(), 0);
+
+        // 定石にない応手の後は内側のエージェントが指す
+        let board = board_after("f5f6");
+        let expected = inner.next_move(&board);
+        assert_eq!(agent.next_move(&board), expected);
+        assert_eq!(calls.get(), 2);
+    }
+}
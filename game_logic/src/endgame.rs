@@ -0,0 +1,105 @@
+use crate::{
+    board::Board,
+    evaluation::ordered_moves,
+    types::{Color, Coordinate},
+};
+
+// これより空きマスが少ない局面では手の並べ替えをせずに読む
+const ORDERING_EMPTIES: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solution {
+    // 手番側から見た最終的な石差 (空きマスは勝った側に数える)
+    pub disc_diff: i32,
+    // 合法手がない (パスまたは終局) 場合は None
+    pub best_move: Option<Coordinate>,
+}
+
+// 終局まで読み切り、最善手と石差を求める
+pub fn solve(board: &Board, turn: Color) -> Solution {
+    let mut board = *board;
+    if board.legal_moves(turn) == 0 {
+        return Solution {
+            disc_diff: search(&mut board, turn, -64, 64),
+            best_move: None,
+        };
+    }
+
+    let mut alpha = -65;
+    let mut best_move = None;
+    for (coord, flips) in ordered_moves(&board, turn) {
+        board.make_move(coord, turn, flips);
+        let score = -search(&mut board, turn.opponent(), -64, -alpha);
+        board.unmake_move(coord, turn, flips);
+        if score > alpha {
+            alpha = score;
+            best_move = Some(coord);
+        }
+    }
+    Solution {
+        disc_diff: alpha,
+        best_move,
+    }
+}
+
+// 合法手それぞれについて、指した後の石差を読み切る (枝刈りしないぶん solve より遅い)
+pub fn solve_moves(board: &Board, turn: Color) -> Vec<(Coordinate, i32)> {
+    let mut board = *board;
+    ordered_moves(&board, turn)
+        .into_iter()
+        .map(|(coord, flips)| {
+            board.make_move(coord, turn, flips);
+            let score = -search(&mut board, turn.opponent(), -64, 64);
+            board.unmake_move(coord, turn, flips);
+            (coord, score)
+        })
+        .collect()
+}
+
+fn search(board: &mut Board, turn: Color, mut alpha: i32, beta: i32) -> i32 {
+    let mut legal_moves = board.legal_moves(turn);
+    if legal_moves == 0 {
+        if board.legal_moves(turn.opponent()) == 0 {
+            return final_disc_diff(board, turn);
+        }
+        // パス
+        return -search(board, turn.opponent(), -beta, -alpha);
+    }
+
+    if board.count_empties() > ORDERING_EMPTIES {
+        for (coord, flips) in ordered_moves(board, turn) {
+            board.make_move(coord, turn, flips);
+            let score = -search(board, turn.opponent(), -beta, -alpha);
+            board.unmake_move(coord, turn, flips);
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        return alpha;
+    }
+
+    while legal_moves != 0 {
+        let coord = Coordinate::from_index(legal_moves.trailing_zeros());
+        legal_moves &= legal_moves - 1;
+        let flips = board.flips(coord, turn);
+        board.make_move(coord, turn, flips);
+        let score = -search(board, turn.opponent(), -beta, -alpha);
+        board.unmake_move(coord, turn, flips);
+        if score >= beta {
+            return score;
+        }
+        alpha = alpha.max(score);
+    }
+    alpha
+}
+
+fn final_disc_diff(board: &Board, turn: Color) -> i32 {
+    let (own, opponent) = board.own_and_opponent(turn);
+    let diff = own.count_ones() as i32 - opponent.count_ones() as i32;
+    let empties = board.count_empties() as i32;
+    match diff.signum() {
+        0 => 0,
+        sign => diff + sign * empties,
+    }
+}
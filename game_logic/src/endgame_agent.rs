@@ -0,0 +1,43 @@
+use crate::endgame::solve;
+
+use super::{
+    ai_agent::AiAgent,
+    board::Board,
+    types::{AgentType, Color, Coordinate},
+};
+
+// 空きマスが empties 以下になったら、内側のエージェントの代わりに完全読みで指す
+#[derive(Debug)]
+pub struct EndgameAgent {
+    inner: Box<dyn AiAgent>,
+    empties: u32,
+}
+
+impl EndgameAgent {
+    pub fn new(inner: Box<dyn AiAgent>, empties: u32) -> Self {
+        EndgameAgent { inner, empties }
+    }
+}
+
+impl AiAgent for EndgameAgent {
+    fn color(&self) -> Color {
+        self.inner.color()
+    }
+
+    fn next_move(&self, board: &Board) -> Coordinate {
+        if board.count_empties() <= self.empties
+            && let Some(coord) = solve(board, self.color()).best_move
+        {
+            return coord;
+        }
+        self.inner.next_move(board)
+    }
+
+    fn agent_type(&self) -> AgentType {
+        self.inner.agent_type()
+    }
+
+    fn clone_box(&self) -> Box<dyn AiAgent> {
+        Box::new(EndgameAgent::new(self.inner.clone_box(), self.empties))
+    }
+}
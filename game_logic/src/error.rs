@@ -0,0 +1,74 @@
+use std::fmt;
+
+use crate::types::{Color, Coordinate};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameError {
+    OccupiedSquare(Coordinate),
+    OutOfBounds(Coordinate),
+    NoFlips(Coordinate),
+    NotInProgress,
+    WrongTurn(Color),
+    NoAgent,
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::OccupiedSquare(coord) => {
+                write!(f, "Invalid move: {} is already occupied", coord)
+            }
+            GameError::OutOfBounds(coord) => {
+                write!(
+                    f,
+                    "Invalid move: ({}, {}) is out of bounds",
+                    coord.x, coord.y
+                )
+            }
+            GameError::NoFlips(coord) => write!(f, "Invalid move: No pieces to flip at {}", coord),
+            GameError::NotInProgress => write!(f, "Game is not in progress"),
+            GameError::WrongTurn(color) => write!(f, "It is not {:?}'s turn", color),
+            GameError::NoAgent => write!(f, "No AI agent set"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    InvalidCoordinate(String),
+    InvalidPosition(String),
+    IllegalMove { index: usize, error: GameError },
+    IllegalPass { index: usize },
+    InvalidBookLine { line: usize, text: String },
+    // 棋譜は初期局面から始まった対局しか表せない
+    NotFromInitialPosition,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidCoordinate(s) => write!(f, "Invalid coordinate: {:?}", s),
+            NotationError::InvalidPosition(s) => write!(f, "Invalid position: {:?}", s),
+            NotationError::IllegalMove { index, error } => {
+                write!(f, "Illegal move #{}: {}", index + 1, error)
+            }
+            NotationError::IllegalPass { index } => {
+                write!(
+                    f,
+                    "Illegal pass #{}: the player has a legal move",
+                    index + 1
+                )
+            }
+            NotationError::InvalidBookLine { line, text } => {
+                write!(f, "Invalid opening book line {}: {:?}", line, text)
+            }
+            NotationError::NotFromInitialPosition => {
+                write!(f, "The game did not start from the initial position")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
@@ -0,0 +1,114 @@
+use crate::{
+    board::{Board, shift_bits},
+    types::{BitBoard, Color, Coordinate},
+};
+
+const CORNERS: BitBoard = 0x8100_0000_0000_0081;
+
+// 隅に斜めに隣接するマス (b2, g2, b7, g7) と、対応する隅
+const X_SQUARES: [(BitBoard, BitBoard); 4] = [
+    (1 << 9, 1 << 0),
+    (1 << 14, 1 << 7),
+    (1 << 49, 1 << 56),
+    (1 << 54, 1 << 63),
+];
+
+// 隅に辺で隣接するマス (a2, b1 など) と、対応する隅
+const C_SQUARES: [(BitBoard, BitBoard); 4] = [
+    ((1 << 1) | (1 << 8), 1 << 0),
+    ((1 << 6) | (1 << 15), 1 << 7),
+    ((1 << 48) | (1 << 57), 1 << 56),
+    ((1 << 55) | (1 << 62), 1 << 63),
+];
+
+const FILE_A: BitBoard = 0x0101_0101_0101_0101;
+const FILE_H: BitBoard = 0x8080_8080_8080_8080;
+const RANK_1: BitBoard = 0x0000_0000_0000_00ff;
+const RANK_8: BitBoard = 0xff00_0000_0000_0000;
+
+// 各軸について、正方向・負方向それぞれの隣が盤外になるマス
+const AXES: [(i32, BitBoard, BitBoard); 4] = [
+    (1, FILE_H, FILE_A),
+    (8, RANK_8, RANK_1),
+    (7, FILE_A | RANK_8, FILE_H | RANK_1),
+    (9, FILE_H | RANK_8, FILE_A | RANK_1),
+];
+
+const MOBILITY_WEIGHT: i32 = 10;
+const CORNER_WEIGHT: i32 = 80;
+const X_SQUARE_WEIGHT: i32 = 30;
+const C_SQUARE_WEIGHT: i32 = 10;
+const STABILITY_WEIGHT: i32 = 15;
+
+// turn から見た局面の評価値 (大きいほど turn が有利)
+pub fn evaluate(board: &Board, turn: Color) -> i32 {
+    let (own, opponent) = board.own_and_opponent(turn);
+    let empty = !(own | opponent);
+
+    let mobility = board.legal_moves(turn).count_ones() as i32
+        - board.legal_moves(turn.opponent()).count_ones() as i32;
+    let corners = (own & CORNERS).count_ones() as i32 - (opponent & CORNERS).count_ones() as i32;
+    let x_squares =
+        danger_squares(own, empty, &X_SQUARES) - danger_squares(opponent, empty, &X_SQUARES);
+    let c_squares =
+        danger_squares(own, empty, &C_SQUARES) - danger_squares(opponent, empty, &C_SQUARES);
+    let stability =
+        stable_discs(own).count_ones() as i32 - stable_discs(opponent).count_ones() as i32;
+
+    MOBILITY_WEIGHT * mobility + CORNER_WEIGHT * corners
+        - X_SQUARE_WEIGHT * x_squares
+        - C_SQUARE_WEIGHT * c_squares
+        + STABILITY_WEIGHT * stability
+}
+
+// 隅が空いているときだけ、その隣のマスを危険とみなす
+fn danger_squares(discs: BitBoard, empty: BitBoard, squares: &[(BitBoard, BitBoard)]) -> i32 {
+    squares
+        .iter()
+        .filter(|&&(_, corner)| empty & corner != 0)
+        .map(|&(square, _)| (discs & square).count_ones() as i32)
+        .sum()
+}
+
+// 4 つの軸すべてで、少なくとも片側が盤外か確定石である石を確定石とする
+pub fn stable_discs(discs: BitBoard) -> BitBoard {
+    let mut stable = 0;
+    loop {
+        let mut next = discs;
+        for &(shift, positive_edge, negative_edge) in &AXES {
+            let positive = positive_edge | shift_bits(stable, -shift);
+            let negative = negative_edge | shift_bits(stable, shift);
+            next &= positive | negative;
+        }
+        if next == stable {
+            return stable;
+        }
+        stable = next;
+    }
+}
+
+// 相手の合法手が少なくなる手から順に並べる (隅は最優先)
+pub(crate) fn ordered_moves(board: &Board, turn: Color) -> Vec<(Coordinate, BitBoard)> {
+    let mut board = *board;
+    let mut moves = Vec::new();
+    let mut legal_moves = board.legal_moves(turn);
+    while legal_moves != 0 {
+        let coord = Coordinate::from_index(legal_moves.trailing_zeros());
+        legal_moves &= legal_moves - 1;
+        let flips = board.flips(coord, turn);
+        board.make_move(coord, turn, flips);
+        let opponent_mobility = board.legal_moves(turn.opponent()).count_ones() as i32;
+        board.unmake_move(coord, turn, flips);
+        let priority = if coord.to_bit() & CORNERS != 0 {
+            -1
+        } else {
+            opponent_mobility
+        };
+        moves.push((priority, coord, flips));
+    }
+    moves.sort_by_key(|&(priority, _, _)| priority);
+    moves
+        .into_iter()
+        .map(|(_, coord, flips)| (coord, flips))
+        .collect()
+}
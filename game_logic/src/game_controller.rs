@@ -0,0 +1,123 @@
+use crate::{
+    ai_agent::{AiAgent, initialize_agent},
+    error::GameError,
+    game_state::GameState,
+    rng::color_seed,
+    types::{Color, Coordinate, GameStatus, Player},
+};
+
+// GameState の手番に応じて、AI プレイヤーに手を指させる
+#[derive(Debug)]
+pub struct GameController {
+    pub state: GameState,
+    black_agent: Option<Box<dyn AiAgent>>,
+    white_agent: Option<Box<dyn AiAgent>>,
+}
+
+impl GameController {
+    pub fn new(state: GameState) -> Self {
+        GameController::with_seed(state, None)
+    }
+
+    // seed から黒と白のエージェントに別々のシードを配る。同じ seed なら対局を再現できる
+    pub fn with_seed(state: GameState, seed: Option<u64>) -> Self {
+        let agent = |color| match state.players.get(color) {
+            Player::Agent(agent_type) => Some(initialize_agent(
+                agent_type,
+                color,
+                seed.map(|seed| color_seed(seed, color)),
+            )),
+            Player::Human => None,
+        };
+        GameController {
+            black_agent: agent(Color::Black),
+            white_agent: agent(Color::White),
+            state,
+        }
+    }
+
+    fn agent(&self, color: Color) -> Option<&dyn AiAgent> {
+        match color {
+            Color::Black => self.black_agent.as_deref(),
+            Color::White => self.white_agent.as_deref(),
+        }
+    }
+
+    // 人間の着手
+    pub fn play(&mut self, coord: Coordinate) -> Result<(), GameError> {
+        if self.state.is_ai_turn() {
+            return Err(GameError::WrongTurn(self.state.turn.opponent()));
+        }
+        self.state.make_move(coord)
+    }
+
+    // AI の手番なら 1 手指す。人間の手番なら指すエージェントがいないので NoAgent
+    pub fn step(&mut self) -> Result<Coordinate, GameError> {
+        if self.state.status != GameStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
+        let agent = self
+            .agent(self.state.turn)
+            .filter(|_| self.state.is_ai_turn())
+            .ok_or(GameError::NoAgent)?;
+        let coord = agent.next_move(&self.state.board);
+        self.state.make_move(coord)?;
+        Ok(coord)
+    }
+
+    // AI 以外の手番か終局になるまで AI に指させる
+    pub fn run(&mut self) -> Result<(), GameError> {
+        while self.state.is_ai_turn() {
+            self.step()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AgentType, Players};
+
+    fn started(black: Player, white: Player) -> GameState {
+        let mut state = GameState::new();
+        state.players = Players { black, white };
+        state.status = GameStatus::InProgress;
+        state
+    }
+
+    #[test]
+    fn step_without_agent_fails() {
+        let mut controller = GameController::new(started(Player::Human, Player::Human));
+        assert_eq!(controller.step(), Err(GameError::NoAgent));
+        assert_eq!(controller.run(), Ok(()));
+        assert!(controller.state.history.is_empty());
+    }
+
+    #[test]
+    fn human_and_agent_take_turns() {
+        let agent = Player::Agent(AgentType::Random);
+        let mut controller = GameController::with_seed(started(Player::Human, agent), Some(0));
+        assert_eq!(controller.step(), Err(GameError::NoAgent));
+        controller.play("f5".parse().unwrap()).unwrap();
+        // AI の手番に人間は指せない
+        assert_eq!(
+            controller.play("d6".parse().unwrap()),
+            Err(GameError::WrongTurn(Color::Black))
+        );
+        controller.run().unwrap();
+        assert_eq!(controller.state.turn, Color::Black);
+        assert_eq!(controller.state.history.len(), 2);
+    }
+
+    #[test]
+    fn step_after_game_over_fails() {
+        let mut state = started(
+            Player::Agent(AgentType::Random),
+            Player::Agent(AgentType::Random),
+        );
+        state.status = GameStatus::Draw;
+        let mut controller = GameController::new(state);
+        assert_eq!(controller.step(), Err(GameError::NotInProgress));
+    }
+}
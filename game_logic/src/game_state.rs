@@ -1,26 +1,26 @@
 use crate::{
-    ai_agent::AiAgent,
     board::Board,
-    types::{AgentType, Color, Coordinate, GameStatus},
+    error::{GameError, NotationError},
+    types::{BitBoard, Color, Coordinate, GameStatus, Move, Player, Players},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveRecord {
+    pub color: Color,
+    pub action: Move,
+    pub flips: BitBoard,
+}
+
+// 対局のルールだけを扱う。AI の着手は GameController などの呼び出し側が行う
+#[derive(Debug, Clone, PartialEq)]
 pub struct GameState {
     pub board: Board,
     pub turn_count: u32,
     pub turn: Color,
     pub status: GameStatus,
-    pub ai_agent: Option<Box<dyn AiAgent>>,
-}
-
-impl PartialEq for GameState {
-    fn eq(&self, other: &Self) -> bool {
-        self.board == other.board
-            && self.turn_count == other.turn_count
-            && self.turn == other.turn
-            && self.status == other.status
-            && self.agent_type() == other.agent_type()
-    }
+    pub players: Players,
+    pub history: Vec<MoveRecord>,
+    pub redo_history: Vec<MoveRecord>,
 }
 
 impl Default for GameState {
@@ -36,53 +36,277 @@ impl GameState {
             turn_count: 0,
             turn: Color::Black,
             status: GameStatus::BeforeStart,
-            ai_agent: None,
+            players: Players::default(),
+            history: Vec::new(),
+            redo_history: Vec::new(),
         }
     }
 
-    pub fn make_move(&mut self, coord: Coordinate) -> Result<(), String> {
+    // 局面文字列 (Board::to_position_string の形式) から対局を始める
+    pub fn from_position(position: &str) -> Result<Self, NotationError> {
+        let (board, mut turn) = Board::from_position_string(position)?;
+        let status = if board.legal_moves(turn) != 0 {
+            GameStatus::InProgress
+        } else if board.legal_moves(turn.opponent()) != 0 {
+            // 手番側に合法手がなければパス
+            turn = turn.opponent();
+            GameStatus::InProgress
+        } else {
+            final_status(&board)
+        };
+        let (black_count, white_count) = board.count_pieces();
+
+        Ok(GameState {
+            board,
+            turn_count: (black_count + white_count).saturating_sub(4),
+            turn,
+            status,
+            ..GameState::new()
+        })
+    }
+
+    pub fn make_move(&mut self, coord: Coordinate) -> Result<(), GameError> {
+        if self.status != GameStatus::InProgress {
+            return Err(GameError::NotInProgress);
+        }
         let turn = self.turn;
-        self.board.move_piece(coord, turn)?;
+        let flips = self.board.move_piece(coord, turn)?;
+        self.history.push(MoveRecord {
+            color: turn,
+            action: Move::Put(coord),
+            flips,
+        });
+        self.redo_history.clear();
         self.finish_turn();
         Ok(())
     }
 
+    // 直前の着手 (とそれに続く強制パス) を取り消す
+    pub fn undo(&mut self) -> Option<MoveRecord> {
+        loop {
+            let record = self.history.pop()?;
+            self.redo_history.push(record);
+            if let Move::Put(coord) = record.action {
+                self.board.unmake_move(coord, record.color, record.flips);
+                self.turn = record.color;
+                self.turn_count -= 1;
+                self.status = GameStatus::InProgress;
+                return Some(record);
+            }
+        }
+    }
+
+    pub fn redo(&mut self) -> Option<MoveRecord> {
+        let record = self.redo_history.pop()?;
+        if let Move::Put(coord) = record.action {
+            self.board.make_move(coord, record.color, record.flips);
+            self.history.push(record);
+            self.finish_turn();
+        }
+        // パスは finish_turn が記録し直す
+        while self
+            .redo_history
+            .last()
+            .is_some_and(|record| record.action == Move::Pass)
+        {
+            self.redo_history.pop();
+        }
+        Some(record)
+    }
+
+    // history の先頭 ply 件を指した時点の盤面
+    pub fn board_at(&self, ply: usize) -> Board {
+        let mut board = self.board;
+        for record in self.history.iter().skip(ply).rev() {
+            if let Move::Put(coord) = record.action {
+                board.unmake_move(coord, record.color, record.flips);
+            }
+        }
+        board
+    }
+
     pub fn valid_moves(&self) -> Vec<Coordinate> {
         self.board.valid_moves(self.turn)
     }
 
-    pub fn make_agent_move(&mut self) -> Result<(), String> {
-        if let Some(agent) = &self.ai_agent {
-            let coord = agent.next_move(&self.board);
-            self.make_move(coord)
-        } else {
-            Err("No AI agent set".into())
-        }
+    pub fn current_player(&self) -> Player {
+        self.players.get(self.turn)
     }
 
-    pub fn agent_type(&self) -> Option<AgentType> {
-        self.ai_agent.as_ref().map(|agent| agent.agent_type())
+    // 対局中で、手番側が AI なら true
+    pub fn is_ai_turn(&self) -> bool {
+        self.status == GameStatus::InProgress && matches!(self.current_player(), Player::Agent(_))
     }
 
-    pub fn finish_turn(&mut self) {
+    // 対局中で、手番側が盤面をクリックして指す人間なら true
+    pub fn is_human_turn(&self) -> bool {
+        self.status == GameStatus::InProgress && self.current_player() == Player::Human
+    }
+
+    fn finish_turn(&mut self) {
         self.turn_count += 1;
         if self.board.valid_moves(self.turn).is_empty()
             && self.board.valid_moves(self.turn.opponent()).is_empty()
         {
-            let (black_count, white_count) = self.board.count_pieces();
-            self.status = match black_count.cmp(&white_count) {
-                std::cmp::Ordering::Less => GameStatus::Winner(Color::White),
-                std::cmp::Ordering::Equal => GameStatus::Draw,
-                std::cmp::Ordering::Greater => GameStatus::Winner(Color::Black),
-            }
+            self.status = final_status(&self.board);
         } else if !self.board.valid_moves(self.turn.opponent()).is_empty() {
             self.turn = self.turn.opponent();
+        } else {
+            self.history.push(MoveRecord {
+                color: self.turn.opponent(),
+                action: Move::Pass,
+                flips: 0,
+            });
+        }
+    }
+}
+
+fn final_status(board: &Board) -> GameStatus {
+    let (black_count, white_count) = board.count_pieces();
+    match black_count.cmp(&white_count) {
+        std::cmp::Ordering::Less => GameStatus::Winner(Color::White),
+        std::cmp::Ordering::Equal => GameStatus::Draw,
+        std::cmp::Ordering::Greater => GameStatus::Winner(Color::Black),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AgentType;
+
+    // 白が f8 に打つと黒は打てる場所がなくなる
+    const BEFORE_PASS: &str = "-------------------X-------XX-XO---XX-O-----XOXO----O-X---XXX-XO O";
+    // 黒が b3 に打つと白の石がなくなって終局する
+    const BEFORE_WIPEOUT: &str =
+        "------------------OOOX-----XXX-----XXX-----X-------X------------ X";
+
+    #[test]
+    fn forced_pass_is_recorded() {
+        let mut state = GameState::from_position(BEFORE_PASS).unwrap();
+        let turn_count = state.turn_count;
+        state.make_move("f8".parse().unwrap()).unwrap();
+
+        assert_eq!(state.status, GameStatus::InProgress);
+        assert_eq!(state.turn, Color::White);
+        assert_eq!(state.turn_count, turn_count + 1);
+        let actions: Vec<_> = state
+            .history
+            .iter()
+            .map(|record| (record.color, record.action))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (Color::White, Move::Put("f8".parse().unwrap())),
+                (Color::Black, Move::Pass),
+            ]
+        );
+    }
+
+    #[test]
+    fn game_ends_when_neither_side_can_move() {
+        let mut state = GameState::from_position(BEFORE_WIPEOUT).unwrap();
+        state.make_move("b3".parse().unwrap()).unwrap();
+        assert_eq!(state.status, GameStatus::Winner(Color::Black));
+        assert_eq!(
+            state.make_move("a1".parse().unwrap()),
+            Err(GameError::NotInProgress)
+        );
+    }
+
+    #[test]
+    fn final_status_counts_pieces() {
+        let draw = format!("{}{} X", "X".repeat(32), "O".repeat(32));
+        let white_wins = format!("{}{} O", "X".repeat(20), "O".repeat(44));
+        for (position, status) in [
+            (draw, GameStatus::Draw),
+            (white_wins, GameStatus::Winner(Color::White)),
+        ] {
+            assert_eq!(GameState::from_position(&position).unwrap().status, status);
         }
+    }
 
-        let ai_agent_color = self.ai_agent.as_ref().map(|agent| agent.color()).unwrap();
+    #[test]
+    fn reports_whose_turn_it_is() {
+        let agent = Player::Agent(AgentType::Random);
+        for black in [Player::Human, agent] {
+            for white in [Player::Human, agent] {
+                let mut state = GameState::new();
+                state.players = Players { black, white };
+                assert!(!state.is_human_turn() && !state.is_ai_turn());
 
-        if ai_agent_color == self.turn && self.status == GameStatus::InProgress {
-            self.make_agent_move().unwrap();
+                state.status = GameStatus::InProgress;
+                for (turn, player) in [(Color::Black, black), (Color::White, white)] {
+                    state.turn = turn;
+                    assert_eq!(state.current_player(), player);
+                    assert_eq!(state.is_human_turn(), player == Player::Human);
+                    assert_eq!(state.is_ai_turn(), player == agent);
+                }
+            }
         }
     }
+
+    // redo_history 以外の状態
+    fn snapshot(state: &GameState) -> (Board, Color, u32, GameStatus, Vec<MoveRecord>) {
+        (
+            state.board,
+            state.turn,
+            state.turn_count,
+            state.status,
+            state.history.clone(),
+        )
+    }
+
+    #[test]
+    fn undo_and_redo_across_a_pass() {
+        let mut state = GameState::from_position(BEFORE_PASS).unwrap();
+        let start = snapshot(&state);
+        state.make_move("f8".parse().unwrap()).unwrap();
+        let after_pass = snapshot(&state);
+        // パスの後も白の手番
+        let coord = state.valid_moves()[0];
+        state.make_move(coord).unwrap();
+        let end = snapshot(&state);
+
+        state.undo().unwrap();
+        assert_eq!(snapshot(&state), after_pass);
+        // パスの記録も一緒に取り消す
+        assert_eq!(
+            state.undo().unwrap().action,
+            Move::Put("f8".parse().unwrap())
+        );
+        assert_eq!(snapshot(&state), start);
+        assert_eq!(state.undo(), None);
+
+        state.redo().unwrap();
+        assert_eq!(snapshot(&state), after_pass);
+        state.redo().unwrap();
+        assert_eq!(snapshot(&state), end);
+        assert_eq!(state.redo(), None);
+    }
+
+    #[test]
+    fn undo_reopens_a_finished_game() {
+        let mut state = GameState::from_position(BEFORE_WIPEOUT).unwrap();
+        let start = snapshot(&state);
+        state.make_move("b3".parse().unwrap()).unwrap();
+        let end = snapshot(&state);
+
+        state.undo().unwrap();
+        assert_eq!(snapshot(&state), start);
+        assert_eq!(state.status, GameStatus::InProgress);
+        state.redo().unwrap();
+        assert_eq!(snapshot(&state), end);
+    }
+
+    #[test]
+    fn new_move_clears_redo() {
+        let mut state = GameState::new();
+        state.status = GameStatus::InProgress;
+        state.make_move("f5".parse().unwrap()).unwrap();
+        state.undo().unwrap();
+        state.make_move("e6".parse().unwrap()).unwrap();
+        assert_eq!(state.redo(), None);
+    }
 }
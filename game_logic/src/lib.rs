@@ -1,7 +1,22 @@
 pub mod ai_agent;
+pub mod alpha_beta_agent;
 pub mod board;
+pub mod book_agent;
+pub mod endgame;
+pub mod endgame_agent;
+pub mod error;
+pub mod evaluation;
+pub mod game_controller;
 pub mod game_state;
 pub mod monte_carlo;
 pub mod monte_carlo_agent;
+pub mod opening_book;
 pub mod random_agent;
+pub mod rng;
+#[cfg(feature = "serde")]
+mod serde_support;
+pub mod symmetry;
+pub mod transcript;
+pub mod transposition;
 pub mod types;
+mod zobrist;
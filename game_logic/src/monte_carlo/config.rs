@@ -0,0 +1,51 @@
+use web_time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchConfig {
+    pub iterations: u32,
+    // 反復回数に達する前でも、この時間を超えたら探索を打ち切る
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::option_millis"))]
+    pub time_limit: Option<Duration>,
+    // UCB1 の探索項の係数
+    pub exploration: f64,
+    // 空きマスがこの数以下になったら完全読みに切り替える (0 なら切り替えない)
+    pub endgame_empties: u32,
+    // 序盤は定石から指すか
+    pub book: bool,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        SearchConfig {
+            iterations: 1000,
+            time_limit: None,
+            exploration: 2.0,
+            endgame_empties: 10,
+            book: true,
+        }
+    }
+}
+
+impl SearchConfig {
+    pub fn easy() -> Self {
+        SearchConfig {
+            iterations: 100,
+            endgame_empties: 0,
+            ..Default::default()
+        }
+    }
+
+    pub fn normal() -> Self {
+        Self::default()
+    }
+
+    pub fn hard() -> Self {
+        SearchConfig {
+            iterations: 10000,
+            time_limit: Some(Duration::from_secs(3)),
+            endgame_empties: 14,
+            ..Default::default()
+        }
+    }
+}
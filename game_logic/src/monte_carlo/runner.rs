@@ -1,18 +1,51 @@
-use std::rc::Rc;
+use rand::Rng;
+use web_time::Instant;
 
 use crate::{
     board::Board,
     types::{Color, Coordinate},
 };
 
-use super::types::Node;
+use super::{
+    config::SearchConfig,
+    types::{MoveStats, SearchTree},
+};
+
+pub fn get_best_move<R: Rng + ?Sized>(
+    board: &Board,
+    color: &Color,
+    config: &SearchConfig,
+    rng: &mut R,
+) -> Coordinate {
+    let mut tree = SearchTree::new(*board, *color, rng);
+    search(&mut tree, config, rng);
+    tree.best_move()
+}
+
+// 探索して、各合法手の勝率などを返す
+pub fn analyze<R: Rng + ?Sized>(
+    board: &Board,
+    color: &Color,
+    config: &SearchConfig,
+    rng: &mut R,
+) -> Vec<MoveStats> {
+    let mut tree = SearchTree::new(*board, *color, rng);
+    search(&mut tree, config, rng);
+    tree.move_stats()
+}
 
-pub fn get_best_move(board: &Board, color: &Color) -> Coordinate {
-    let monte_node = Rc::new(Node::new(*board, *color, color, None, 0));
-    for total_visits in 1..1001 {
-        let node = monte_node.select(total_visits);
-        let winner = node.simulate();
-        node.backpropagate(winner);
+// time_limit があると反復回数が実行速度で変わるので、同じシードでも結果は一致しない
+pub fn search<R: Rng + ?Sized>(tree: &mut SearchTree, config: &SearchConfig, rng: &mut R) {
+    let start = Instant::now();
+    for _ in 0..config.iterations {
+        if config
+            .time_limit
+            .is_some_and(|time_limit| start.elapsed() >= time_limit)
+        {
+            break;
+        }
+        let node = tree.select(config.exploration, rng);
+        let winner = tree.simulate(node, rng);
+        tree.backpropagate(node, winner);
     }
-    monte_node.best_move()
 }
@@ -1,11 +1,7 @@
-use rand::seq::IndexedRandom;
+use rand::Rng;
 use rand::seq::SliceRandom;
-use std::cell::RefMut;
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    rc::{Rc, Weak},
-};
+use std::collections::VecDeque;
+use std::ops::Range;
 
 use crate::board::Board;
 use crate::types::Color;
@@ -24,153 +20,176 @@ pub fn light_to_coord(light: LightCoord) -> Coordinate {
     }
 }
 
+pub type NodeId = u32;
+
+const ROOT: NodeId = 0;
 const EXPANSION_THRESHOLD: u32 = 10;
 
-pub struct Node<'a> {
-    pub wins: RefCell<u32>,
-    pub visits: RefCell<u32>,
-    pub draws: RefCell<u32>,
-    pub board: RefCell<Board>,
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub wins: u32,
+    pub visits: u32,
+    pub draws: u32,
+    pub board: Board,
     pub turn: Color,
-    pub self_color: &'a Color,
-    pub children: RefCell<HashMap<LightCoord, Rc<Node<'a>>>>,
-    pub parent: RefCell<Option<Weak<Node<'a>>>>,
-    pub valid_moves: RefCell<Vec<LightCoord>>,
-    pub depth: u32,
+    // 親ノードからこのノードに至る手 (ルートでは意味を持たない)
+    pub coord: LightCoord,
+    pub parent: Option<NodeId>,
+    // 展開済みの子ノードは nodes 上で連続して並ぶ
+    pub children: Range<NodeId>,
+    pub expanded: bool,
 }
 
-impl<'a> Node<'a> {
-    pub fn new(
-        board: Board,
-        turn: Color,
-        self_color: &'a Color,
-        parent: Option<Rc<Node<'a>>>,
-        depth: u32,
-    ) -> Self {
-        let mut rng = rand::rng();
-        let mut valid_moves = board.valid_moves(turn);
-        valid_moves.shuffle(&mut rng);
-        let visits = if parent.is_none() {
-            EXPANSION_THRESHOLD + 1
-        } else {
-            0
-        };
-
+impl Node {
+    fn new(board: Board, turn: Color, coord: LightCoord, parent: Option<NodeId>) -> Self {
         Node {
-            wins: RefCell::new(0),
-            visits: RefCell::new(visits),
-            draws: RefCell::new(0),
-            board: RefCell::new(board),
+            wins: 0,
+            visits: 0,
+            draws: 0,
+            board,
             turn,
-            self_color,
-            children: RefCell::new(HashMap::new()),
-            parent: RefCell::new(parent.map(|p| Rc::downgrade(&p))),
-            valid_moves: RefCell::new(
-                valid_moves
-                    .iter()
-                    .map(|&coord| coord_to_light(coord))
-                    .collect(),
-            ),
-            depth,
+            coord,
+            parent,
+            children: 0..0,
+            expanded: false,
         }
     }
 
-    pub fn select(self: &Rc<Self>, total_visits: u32) -> Rc<Node<'a>> {
-        let mut valid_moves = self.valid_moves.borrow_mut();
-        let visits = *self.visits.borrow();
-        if visits < EXPANSION_THRESHOLD {
-            return Rc::clone(self);
-        }
-        if !valid_moves.is_empty() {
-            let c = self.expand(&mut valid_moves);
-            return self.children.borrow().get(&c).unwrap().clone();
+    // 親の手番側から見た UCB1
+    pub fn ucb1(&self, parent_visits: u32, exploration: f64) -> f64 {
+        if self.visits == 0 {
+            return f64::INFINITY;
         }
+        let exploitation = (self.wins * 2 + self.draws) as f64 / (self.visits * 2) as f64;
+        let exploration = exploration * (parent_visits as f64).ln() / self.visits as f64;
+        exploitation + exploration.sqrt()
+    }
+}
 
-        let mut best_ucb1 = f64::NEG_INFINITY;
-        let mut best_node = None;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveStats {
+    pub coord: Coordinate,
+    pub wins: u32,
+    pub visits: u32,
+    pub draws: u32,
+}
 
-        let children = self.children.borrow();
-        if children.is_empty() {
-            return Rc::clone(self);
-        }
-        for child in children.values() {
-            let ucb1 = child.ucb1(total_visits);
-            if ucb1 > best_ucb1 {
-                best_ucb1 = ucb1;
-                best_node = Some(child);
-            }
+impl MoveStats {
+    // ルートの手番側から見た勝率。引き分けは半分の勝ちとして数える
+    pub fn win_rate(&self) -> f64 {
+        if self.visits == 0 {
+            return 0.5;
         }
-
-        best_node.unwrap().select(total_visits)
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.visits as f64
     }
+}
 
-    pub fn best_move(self: &Rc<Self>) -> Coordinate {
-        let children = self.children.borrow();
-        let mut best_visits: i32 = -1;
-        let mut best_coord = 0;
-        for (coord, child) in children.iter() {
-            let visits = *child.visits.borrow() as i32;
-            if best_visits < visits {
-                best_visits = visits;
-                best_coord = *coord;
-            }
-        }
-        light_to_coord(best_coord)
+// ノードを Vec に並べたモンテカルロ木。ルートは常に先頭
+#[derive(Debug, Clone)]
+pub struct SearchTree {
+    pub nodes: Vec<Node>,
+}
+
+impl SearchTree {
+    pub fn new<R: Rng + ?Sized>(board: Board, turn: Color, rng: &mut R) -> Self {
+        let mut tree = SearchTree {
+            nodes: vec![Node::new(board, turn, 0, None)],
+        };
+        tree.expand(ROOT, rng);
+        tree
     }
 
-    pub fn expand(self: &Rc<Self>, valid_moves: &mut RefMut<'_, Vec<u8>>) -> LightCoord {
-        let valid_move = *valid_moves.last().unwrap();
-        valid_moves.pop();
+    pub fn root(&self) -> &Node {
+        &self.nodes[ROOT as usize]
+    }
 
-        let mut new_board = *self.board.borrow();
-        new_board
-            .move_piece(light_to_coord(valid_move), self.turn)
-            .unwrap();
+    pub fn children(&self, id: NodeId) -> &[Node] {
+        let range = &self.nodes[id as usize].children;
+        &self.nodes[range.start as usize..range.end as usize]
+    }
 
-        let turn = if new_board.valid_moves(self.turn.opponent()).is_empty() {
-            self.turn
-        } else {
-            self.turn.opponent()
-        };
+    pub fn select<R: Rng + ?Sized>(&mut self, exploration: f64, rng: &mut R) -> NodeId {
+        let mut id = ROOT;
+        loop {
+            let node = &self.nodes[id as usize];
+            if !node.expanded {
+                if node.visits < EXPANSION_THRESHOLD {
+                    return id;
+                }
+                self.expand(id, rng);
+            }
 
-        let new_node = Node::new(
-            new_board,
-            turn,
-            self.self_color,
-            Some(Rc::clone(self)),
-            self.depth + 1,
-        );
+            let node = &self.nodes[id as usize];
+            if node.children.is_empty() {
+                return id;
+            }
+            let parent_visits = node.visits;
+            let mut best_ucb1 = f64::NEG_INFINITY;
+            let mut best_child = node.children.start;
+            for child in node.children.clone() {
+                let ucb1 = self.nodes[child as usize].ucb1(parent_visits, exploration);
+                if ucb1 > best_ucb1 {
+                    best_ucb1 = ucb1;
+                    best_child = child;
+                }
+            }
+            id = best_child;
+        }
+    }
 
-        self.children
-            .borrow_mut()
-            .insert(valid_move, Rc::new(new_node));
+    pub fn expand<R: Rng + ?Sized>(&mut self, id: NodeId, rng: &mut R) {
+        let node = &self.nodes[id as usize];
+        let (board, turn) = (node.board, node.turn);
+        let mut valid_moves = board.valid_moves(turn);
+        valid_moves.shuffle(rng);
 
-        valid_move
+        let start = self.nodes.len() as NodeId;
+        for coord in valid_moves {
+            let mut new_board = board;
+            new_board.move_piece(coord, turn).unwrap();
+            let next_turn = if new_board.legal_moves(turn.opponent()) == 0 {
+                turn
+            } else {
+                turn.opponent()
+            };
+            self.nodes.push(Node::new(
+                new_board,
+                next_turn,
+                coord_to_light(coord),
+                Some(id),
+            ));
+        }
+        let end = self.nodes.len() as NodeId;
+        let node = &mut self.nodes[id as usize];
+        node.children = start..end;
+        node.expanded = true;
     }
 
-    pub fn backpropagate(self: &Rc<Self>, winner: Option<Color>) {
-        *self.visits.borrow_mut() += 1;
-        if let Some(winner) = winner {
-            if winner != self.turn {
-                *self.wins.borrow_mut() += 1;
+    pub fn backpropagate(&mut self, id: NodeId, winner: Option<Color>) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let parent = self.nodes[id as usize].parent;
+            // 勝ち数はこのノードに至る手を指した側 (親の手番) から数える
+            let mover = parent.map(|parent| self.nodes[parent as usize].turn);
+            let node = &mut self.nodes[id as usize];
+            node.visits += 1;
+            match winner {
+                Some(winner) if Some(winner) == mover => node.wins += 1,
+                Some(_) => {}
+                None => node.draws += 1,
             }
-        } else {
-            *self.draws.borrow_mut() += 1;
-        }
-        let parent_opt = self.parent.borrow().as_ref().and_then(|p| p.upgrade());
-        if let Some(parent) = parent_opt {
-            parent.backpropagate(winner);
+            current = parent;
         }
     }
 
-    pub fn simulate(self: &Rc<Self>) -> Option<Color> {
-        let mut board = *self.board.borrow();
-        let mut turn = self.turn;
+    pub fn simulate<R: Rng + ?Sized>(&self, id: NodeId, rng: &mut R) -> Option<Color> {
+        let node = &self.nodes[id as usize];
+        let mut board = node.board;
+        let mut turn = node.turn;
         let mut pass = false;
-        let mut rng = rand::rng();
         loop {
-            let valid_moves = board.valid_moves(turn);
-            if valid_moves.is_empty() {
+            let mut legal_moves = board.legal_moves(turn);
+            if legal_moves == 0 {
                 if pass {
                     break;
                 }
@@ -179,7 +198,10 @@ impl<'a> Node<'a> {
                 continue;
             }
             pass = false;
-            let coord = *valid_moves.choose(&mut rng).unwrap();
+            for _ in 0..rng.random_range(0..legal_moves.count_ones()) {
+                legal_moves &= legal_moves - 1;
+            }
+            let coord = Coordinate::from_index(legal_moves.trailing_zeros());
             board.move_piece(coord, turn).unwrap();
             turn = turn.opponent();
         }
@@ -191,15 +213,143 @@ impl<'a> Node<'a> {
         }
     }
 
-    pub fn ucb1(self: &Rc<Self>, total_visits: u32) -> f64 {
-        let visits = *self.visits.borrow();
-        if *self.visits.borrow() == 0 {
-            return f64::INFINITY;
+    // ルートの各手の探索結果
+    pub fn move_stats(&self) -> Vec<MoveStats> {
+        self.children(ROOT)
+            .iter()
+            .map(|child| MoveStats {
+                coord: light_to_coord(child.coord),
+                wins: child.wins,
+                visits: child.visits,
+                draws: child.draws,
+            })
+            .collect()
+    }
+
+    pub fn best_move(&self) -> Coordinate {
+        let best = self
+            .children(ROOT)
+            .iter()
+            .max_by_key(|child| child.visits)
+            .unwrap();
+        light_to_coord(best.coord)
+    }
+
+    // coord を指した後の部分木に移る
+    pub fn descend<R: Rng + ?Sized>(self, coord: Coordinate, rng: &mut R) -> Option<Self> {
+        let light = coord_to_light(coord);
+        let range = self.root().children.clone();
+        range
+            .into_iter()
+            .find(|&child| self.nodes[child as usize].coord == light)
+            .map(|child| self.subtree(child, rng))
+    }
+
+    // 自分の手と相手の応手の 2 手以内で board に一致する部分木を探す
+    pub fn find<R: Rng + ?Sized>(self, board: &Board, turn: Color, rng: &mut R) -> Option<Self> {
+        let matches = |id: NodeId| {
+            let node = &self.nodes[id as usize];
+            node.board == *board && node.turn == turn
+        };
+        if matches(ROOT) {
+            return Some(self);
         }
-        let draws = *self.draws.borrow();
-        let wins = *self.wins.borrow();
-        let exploitation = (wins * 2 + draws) as f64 / (visits * 2) as f64;
-        let exploration = 2.0 * (total_visits as f64).ln() / visits as f64;
-        exploitation + exploration.sqrt()
+        for child in self.root().children.clone() {
+            if matches(child) {
+                return Some(self.subtree(child, rng));
+            }
+            for grandchild in self.nodes[child as usize].children.clone() {
+                if matches(grandchild) {
+                    return Some(self.subtree(grandchild, rng));
+                }
+            }
+        }
+        None
+    }
+
+    // id 以下の部分木を、子が連続する並びを保ったまま新しい木にコピーする
+    fn subtree<R: Rng + ?Sized>(&self, id: NodeId, rng: &mut R) -> Self {
+        let mut nodes = vec![Node {
+            parent: None,
+            ..self.nodes[id as usize].clone()
+        }];
+        let mut queue = VecDeque::from([(id, ROOT)]);
+        while let Some((old_id, new_id)) = queue.pop_front() {
+            let start = nodes.len() as NodeId;
+            for child in self.nodes[old_id as usize].children.clone() {
+                queue.push_back((child, nodes.len() as NodeId));
+                nodes.push(Node {
+                    parent: Some(new_id),
+                    ..self.nodes[child as usize].clone()
+                });
+            }
+            nodes[new_id as usize].children = start..nodes.len() as NodeId;
+        }
+        let mut tree = SearchTree { nodes };
+        if !tree.root().expanded {
+            tree.expand(ROOT, rng);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        monte_carlo::{config::SearchConfig, runner::search},
+        rng::seeded_rng,
+    };
+
+    fn child_visits(tree: &SearchTree, id: NodeId) -> Vec<(LightCoord, u32)> {
+        tree.children(id)
+            .iter()
+            .map(|child| (child.coord, child.visits))
+            .collect()
+    }
+
+    // 自分の手と相手の応手の後も、その局面の統計をそのまま引き継ぐ
+    #[test]
+    fn reused_subtree_keeps_statistics() {
+        let mut rng = seeded_rng(Some(1));
+        let mut tree = SearchTree::new(Board::new(), Color::Black, &mut rng);
+        let config = SearchConfig {
+            iterations: 3000,
+            ..SearchConfig::default()
+        };
+        search(&mut tree, &config, &mut rng);
+
+        let own = tree.best_move();
+        let child = tree
+            .root()
+            .children
+            .clone()
+            .find(|&id| tree.nodes[id as usize].coord == coord_to_light(own))
+            .unwrap();
+        let reply = tree.nodes[child as usize]
+            .children
+            .clone()
+            .max_by_key(|&id| tree.nodes[id as usize].visits)
+            .unwrap();
+        let child_node = tree.nodes[child as usize].clone();
+        let reply_node = tree.nodes[reply as usize].clone();
+        let reply_children = child_visits(&tree, reply);
+        assert!(!reply_children.is_empty());
+
+        let tree = tree.descend(own, &mut rng).unwrap();
+        assert_eq!(tree.root().board, child_node.board);
+        assert_eq!(tree.root().visits, child_node.visits);
+        assert_eq!(tree.root().parent, None);
+
+        let other = tree.clone();
+        let tree = tree
+            .find(&reply_node.board, reply_node.turn, &mut rng)
+            .unwrap();
+        assert_eq!(tree.root().visits, reply_node.visits);
+        assert_eq!(tree.root().wins, reply_node.wins);
+        assert_eq!(child_visits(&tree, ROOT), reply_children);
+
+        // 2 手以内に現れない局面は見つからない
+        assert!(other.find(&Board::new(), Color::Black, &mut rng).is_none());
     }
 }
@@ -1,19 +1,31 @@
-use crate::monte_carlo::runner::get_best_move;
+use std::{cell::RefCell, rc::Rc};
+
+use crate::monte_carlo::{config::SearchConfig, runner::search, types::SearchTree};
 
 use super::{
     ai_agent::AiAgent,
     board::Board,
+    rng::SharedRng,
     types::{AgentType, Color, Coordinate},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct MonteCarloAgent {
     color: Color,
+    config: SearchConfig,
+    // 前回の着手後の探索木。clone したエージェント同士で共有する
+    tree: Rc<RefCell<Option<SearchTree>>>,
+    rng: SharedRng,
 }
 
 impl MonteCarloAgent {
-    pub fn new(color: Color) -> Self {
-        MonteCarloAgent { color }
+    pub fn new(color: Color, config: SearchConfig, rng: SharedRng) -> Self {
+        MonteCarloAgent {
+            color,
+            config,
+            tree: Rc::new(RefCell::new(None)),
+            rng,
+        }
     }
 }
 
@@ -23,10 +35,23 @@ impl AiAgent for MonteCarloAgent {
     }
 
     fn next_move(&self, board: &Board) -> Coordinate {
-        get_best_move(board, &self.color)
+        let mut stored = self.tree.borrow_mut();
+        let rng = &mut *self.rng.borrow_mut();
+        let mut tree = stored
+            .take()
+            .and_then(|tree| tree.find(board, self.color, rng))
+            .unwrap_or_else(|| SearchTree::new(*board, self.color, rng));
+        search(&mut tree, &self.config, rng);
+        let coord = tree.best_move();
+        *stored = tree.descend(coord, rng);
+        coord
     }
 
     fn agent_type(&self) -> super::types::AgentType {
-        AgentType::MonteCarlo
+        AgentType::MonteCarlo(self.config)
+    }
+
+    fn clone_box(&self) -> Box<dyn AiAgent> {
+        Box::new(self.clone())
     }
 }
@@ -0,0 +1,266 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    board::Board,
+    error::NotationError,
+    game_state::GameState,
+    transcript::Transcript,
+    types::{Color, Coordinate, GameStatus},
+};
+
+// 最善の値からこの石差以内の手は同じくらい良い手として扱い、その中から選ぶ
+const SCORE_MARGIN: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookMove {
+    pub coord: Coordinate,
+    // 指す側から見た石差の目安
+    pub score: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    // 正規化した盤面での座標
+    moves: Vec<Coordinate>,
+    // 棋譜がこの局面で終わっていれば、その行の値 (黒から見た石差)
+    score: Option<i32>,
+}
+
+// 局面は対称変換で正規化して持つので、回転・反転した局面でも引ける
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    positions: HashMap<(Board, Color), Node>,
+}
+
+// 着手後の局面と手番。終局なら None
+fn play(board: &Board, turn: Color, coord: Coordinate) -> Option<(Board, Color)> {
+    let mut board = *board;
+    board.move_piece(coord, turn).ok()?;
+    if board.legal_moves(turn.opponent()) != 0 {
+        Some((board, turn.opponent()))
+    } else if board.legal_moves(turn) != 0 {
+        Some((board, turn))
+    } else {
+        None
+    }
+}
+
+impl OpeningBook {
+    // "f5d6c3 0" のように、棋譜と最後の局面での黒から見た石差を 1 行ずつ書いたテキストを読む
+    // '#' 以降はコメント
+    pub fn parse(text: &str) -> Result<Self, NotationError> {
+        let mut book = OpeningBook::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || NotationError::InvalidBookLine {
+                line: index + 1,
+                text: line.to_string(),
+            };
+            let (moves, score) = line.rsplit_once(char::is_whitespace).ok_or_else(invalid)?;
+            let score = score.parse().map_err(|_| invalid())?;
+            book.add_line(&moves.parse().map_err(|_| invalid())?, score)
+                .map_err(|_| invalid())?;
+        }
+        Ok(book)
+    }
+
+    // 組み込みの定石
+    pub fn standard() -> Arc<OpeningBook> {
+        static BOOK: OnceLock<Arc<OpeningBook>> = OnceLock::new();
+        BOOK.get_or_init(|| {
+            Arc::new(
+                OpeningBook::parse(include_str!("../book/opening.txt"))
+                    .expect("the built-in opening book is valid"),
+            )
+        })
+        .clone()
+    }
+
+    // score は棋譜の最後の局面での黒から見た石差。同じ局面で終わる行は後のもので上書きする
+    // 不正な手を含む行は何も登録せずにエラーを返す
+    pub fn add_line(&mut self, transcript: &Transcript, score: i32) -> Result<(), NotationError> {
+        let mut state = GameState::new();
+        state.status = GameStatus::InProgress;
+        let mut entries = Vec::with_capacity(transcript.moves().len());
+        for (index, &coord) in transcript.moves().iter().enumerate() {
+            let (board, symmetry) = state.board.canonical();
+            let turn = state.turn;
+            state
+                .make_move(coord)
+                .map_err(|error| NotationError::IllegalMove { index, error })?;
+            entries.push(((board, turn), coord.transform(symmetry)));
+        }
+
+        for (key, coord_in_book) in entries {
+            let node = self.positions.entry(key).or_default();
+            if !node.moves.contains(&coord_in_book) {
+                node.moves.push(coord_in_book);
+            }
+        }
+        let (board, _) = state.board.canonical();
+        self.positions.entry((board, state.turn)).or_default().score = Some(score);
+        Ok(())
+    }
+
+    // 正規化した局面の値 (黒から見た石差)。行末の値からミニマックスで求める
+    fn value(&self, board: &Board, turn: Color) -> Option<i32> {
+        let node = self.positions.get(&(*board, turn))?;
+        let values = node.moves.iter().filter_map(|&coord| {
+            let (board, turn) = play(board, turn, coord)?;
+            let (board, _) = board.canonical();
+            self.value(&board, turn)
+        });
+        let value = match turn {
+            Color::Black => values.max(),
+            Color::White => values.min(),
+        };
+        value.or(node.score)
+    }
+
+    // 局面に登録された手 (実際の盤面の座標に戻したもの)
+    pub fn lookup(&self, board: &Board, turn: Color) -> Vec<BookMove> {
+        let (canonical, symmetry) = board.canonical();
+        let Some(node) = self.positions.get(&(canonical, turn)) else {
+            return Vec::new();
+        };
+        let sign = match turn {
+            Color::Black => 1,
+            Color::White => -1,
+        };
+        node.moves
+            .iter()
+            .filter_map(|&coord| {
+                let (child, child_turn) = play(&canonical, turn, coord)?;
+                let (child, _) = child.canonical();
+                Some(BookMove {
+                    coord: coord.transform(symmetry.inverse()),
+                    score: sign * self.value(&child, child_turn)?,
+                })
+            })
+            .collect()
+    }
+
+    // 定石手から 1 つ選ぶ。定石を外れていれば None
+    pub fn choose<R: Rng + ?Sized>(
+        &self,
+        board: &Board,
+        turn: Color,
+        rng: &mut R,
+    ) -> Option<Coordinate> {
+        let moves = self.lookup(board, turn);
+        let best = moves.iter().map(|book_move| book_move.score).max()?;
+        let candidates: Vec<_> = moves
+            .into_iter()
+            .filter(|book_move| book_move.score >= best - SCORE_MARGIN)
+            .collect();
+        candidates.choose(rng).map(|book_move| book_move.coord)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng::seeded_rng, symmetry::Symmetry};
+
+    fn after(moves: &str) -> (Board, Color) {
+        let state = moves.parse::<Transcript>().unwrap().replay().unwrap();
+        (state.board, state.turn)
+    }
+
+    #[test]
+    fn parses_builtin_book() {
+        let book = OpeningBook::parse(include_str!("../book/opening.txt")).unwrap();
+        let (board, turn) = after("");
+        assert!(!book.lookup(&board, turn).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for (text, line) in [("f5d6 0\nf5f5 0", 2), ("# comment\nf5d6", 2), ("f5 x", 1)] {
+            match OpeningBook::parse(text) {
+                Err(NotationError::InvalidBookLine { line: actual, .. }) => {
+                    assert_eq!(actual, line, "{text:?}")
+                }
+                result => panic!("{text:?}: {result:?}"),
+            }
+        }
+    }
+
+    // 4 通りの初手は互いに対称なので、定石手も同じ変換で移り合う
+    #[test]
+    fn lookup_is_symmetric_across_first_moves() {
+        let book = OpeningBook::standard();
+        let (f5, turn) = after("f5");
+        let mut expected = book.lookup(&f5, turn);
+        assert!(!expected.is_empty());
+        expected.sort_by_key(|book_move| book_move.coord.to_string());
+
+        for first in ["f5", "e6", "d3", "c4"] {
+            let (board, turn) = after(first);
+            let symmetry = Symmetry::ALL
+                .into_iter()
+                .find(|&symmetry| f5.transform(symmetry) == board)
+                .unwrap();
+            let mut moves = book.lookup(&board, turn);
+            for book_move in &moves {
+                assert!(
+                    board.flips(book_move.coord, turn) != 0,
+                    "{first}: {}",
+                    book_move.coord
+                );
+            }
+            moves
+                .sort_by_key(|book_move| book_move.coord.transform(symmetry.inverse()).to_string());
+            let moves: Vec<_> = moves
+                .into_iter()
+                .map(|book_move| BookMove {
+                    coord: book_move.coord.transform(symmetry.inverse()),
+                    ..book_move
+                })
+                .collect();
+            assert_eq!(moves, expected, "{first}");
+        }
+    }
+
+    #[test]
+    fn values_follow_minimax() {
+        let book = OpeningBook::parse("f5d6c3 4\nf5d6c5 -2\nf5f6 1\nf5f4 5").unwrap();
+        let score_of = |moves: &str, coord: &str| {
+            let (board, turn) = after(moves);
+            book.lookup(&board, turn)
+                .into_iter()
+                .find(|book_move| book_move.coord.to_string() == coord)
+                .map(|book_move| book_move.score)
+        };
+        // 黒は d6 の後で c3 (+4) を選ぶ
+        assert_eq!(score_of("f5d6", "c3"), Some(4));
+        assert_eq!(score_of("f5d6", "c5"), Some(-2));
+        // 白から見た値。白は f6 (黒から見て +1) を選ぶ
+        assert_eq!(score_of("f5", "d6"), Some(-4));
+        assert_eq!(score_of("f5", "f6"), Some(-1));
+        assert_eq!(score_of("f5", "f4"), Some(-5));
+        assert_eq!(score_of("", "f5"), Some(1));
+        assert_eq!(score_of("f5", "e3"), None);
+    }
+
+    #[test]
+    fn choose_stays_within_margin() {
+        let book = OpeningBook::parse("f5d6c3 4\nf5d6c5 -2\nf5d6c4 3").unwrap();
+        let (board, turn) = after("f5d6");
+        let mut rng = seeded_rng(Some(0));
+        for _ in 0..20 {
+            let coord = book.choose(&board, turn, &mut rng).unwrap().to_string();
+            assert!(coord == "c3" || coord == "c4", "{coord}");
+        }
+        let (board, turn) = after("f5f6");
+        assert_eq!(book.choose(&board, turn, &mut rng), None);
+    }
+}
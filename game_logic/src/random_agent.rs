@@ -3,17 +3,19 @@ use rand::seq::IndexedRandom;
 use super::{
     ai_agent::AiAgent,
     board::Board,
+    rng::SharedRng,
     types::{AgentType, Color, Coordinate},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct RandomAgent {
     color: Color,
+    rng: SharedRng,
 }
 
 impl RandomAgent {
-    pub fn new(color: Color) -> Self {
-        RandomAgent { color }
+    pub fn new(color: Color, rng: SharedRng) -> Self {
+        RandomAgent { color, rng }
     }
 }
 
@@ -23,11 +25,15 @@ impl AiAgent for RandomAgent {
     }
 
     fn next_move(&self, board: &Board) -> Coordinate {
-        let mut rng = rand::rng();
-        *board.valid_moves(self.color).choose(&mut rng).unwrap()
+        let mut rng = self.rng.borrow_mut();
+        *board.valid_moves(self.color).choose(&mut *rng).unwrap()
     }
 
     fn agent_type(&self) -> super::types::AgentType {
         AgentType::Random
     }
+
+    fn clone_box(&self) -> Box<dyn AiAgent> {
+        Box::new(self.clone())
+    }
 }
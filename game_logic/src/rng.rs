@@ -0,0 +1,36 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::types::Color;
+
+// エージェントと探索が使う乱数生成器。同じシードなら環境によらず同じ列を返す
+pub type AgentRng = ChaCha8Rng;
+
+// clone したエージェント同士で共有する乱数生成器
+pub type SharedRng = Rc<RefCell<AgentRng>>;
+
+// シードがなければ OS の乱数で初期化する
+pub fn seeded_rng(seed: Option<u64>) -> AgentRng {
+    match seed {
+        Some(seed) => AgentRng::seed_from_u64(seed),
+        None => AgentRng::from_rng(&mut rand::rng()),
+    }
+}
+
+// 対局のシードから色ごとのエージェントのシードを作る。相手が人間でも AI でも同じ値になる
+pub fn color_seed(seed: u64, color: Color) -> u64 {
+    let mut rng = seeded_rng(Some(seed));
+    let black = rng.random();
+    let white = rng.random();
+    match color {
+        Color::Black => black,
+        Color::White => white,
+    }
+}
+
+// rng から独立した系列の乱数生成器を作る
+pub fn split_rng(rng: &mut AgentRng) -> SharedRng {
+    Rc::new(RefCell::new(AgentRng::from_rng(rng)))
+}
@@ -0,0 +1,137 @@
+// serde 用の表現。座標は "f5" 形式、盤面は 16 桁の 16 進数で表す
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{
+    board::Board,
+    types::{BitBoard, Coordinate},
+};
+
+impl Serialize for Coordinate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Coordinate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardRepr {
+    black: String,
+    white: String,
+}
+
+fn parse_bitboard<E: de::Error>(s: &str) -> Result<BitBoard, E> {
+    BitBoard::from_str_radix(s, 16).map_err(E::custom)
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoardRepr {
+            black: format!("{:016x}", self.black()),
+            white: format!("{:016x}", self.white()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = BoardRepr::deserialize(deserializer)?;
+        Board::from_bitboards(parse_bitboard(&repr.black)?, parse_bitboard(&repr.white)?)
+            .map_err(de::Error::custom)
+    }
+}
+
+// Option<Duration> をミリ秒の整数で表す
+pub(crate) mod option_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use web_time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&(duration.as_millis() as u64)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use serde_json::json;
+    use web_time::Duration;
+
+    use crate::{monte_carlo::config::SearchConfig, types::Color};
+
+    use super::*;
+
+    #[test]
+    fn coordinate_is_algebraic() {
+        let coord: Coordinate = "f5".parse().unwrap();
+        assert_eq!(serde_json::to_value(coord).unwrap(), json!("f5"));
+        assert_eq!(
+            serde_json::from_value::<Coordinate>(json!("f5")).unwrap(),
+            coord
+        );
+        assert!(serde_json::from_value::<Coordinate>(json!("z9")).is_err());
+        assert!(serde_json::from_value::<Coordinate>(json!(5)).is_err());
+    }
+
+    #[test]
+    fn board_is_hex_bitboards() {
+        let mut board = Board::new();
+        board
+            .move_piece("f5".parse().unwrap(), Color::Black)
+            .unwrap();
+        let value = serde_json::to_value(board).unwrap();
+        assert_eq!(
+            value,
+            json!({"black": "0000003810000000", "white": "0000000008000000"})
+        );
+        let decoded: Board = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, board);
+        assert_eq!(decoded.hash(), board.hash());
+
+        // 重なった石や 16 進数でない値は読まない
+        for (black, white) in [("1", "1"), ("xyz", "0"), ("10000000000000000", "0")] {
+            assert!(
+                serde_json::from_value::<Board>(json!({"black": black, "white": white})).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn time_limit_is_millis() {
+        let config = SearchConfig {
+            time_limit: Some(Duration::from_millis(1500)),
+            ..SearchConfig::default()
+        };
+        let value = serde_json::to_value(config).unwrap();
+        assert_eq!(value["time_limit"], json!(1500));
+        assert_eq!(
+            serde_json::from_value::<SearchConfig>(value).unwrap(),
+            config
+        );
+
+        let value = serde_json::to_value(SearchConfig::default()).unwrap();
+        assert_eq!(value["time_limit"], json!(null));
+        assert_eq!(
+            serde_json::from_value::<SearchConfig>(value).unwrap(),
+            SearchConfig::default()
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    board::Board,
+    types::{BitBoard, Coordinate},
+};
+
+// 盤面の 8 通りの対称変換 (回転と裏返し)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    // Coordinate::right_rotate と同じ向きに 90 度回す
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    // 左右反転 (x -> 7 - x)
+    FlipHorizontal,
+    // 上下反転 (y -> 7 - y)
+    FlipVertical,
+    // a1-h8 の対角線で反転 (x と y を入れ替える)
+    FlipDiagonal,
+    // h1-a8 の対角線で反転
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            symmetry => symmetry,
+        }
+    }
+
+    pub fn apply_bits(self, bits: BitBoard) -> BitBoard {
+        match self {
+            Symmetry::Identity => bits,
+            Symmetry::Rotate90 => flip_horizontal(flip_diagonal(bits)),
+            Symmetry::Rotate180 => bits.reverse_bits(),
+            Symmetry::Rotate270 => flip_diagonal(bits).swap_bytes(),
+            Symmetry::FlipHorizontal => flip_horizontal(bits),
+            Symmetry::FlipVertical => bits.swap_bytes(),
+            Symmetry::FlipDiagonal => flip_diagonal(bits),
+            Symmetry::FlipAntiDiagonal => flip_diagonal(bits).reverse_bits(),
+        }
+    }
+}
+
+// 各行の中でビットを左右反転する
+fn flip_horizontal(bits: BitBoard) -> BitBoard {
+    const K1: BitBoard = 0x5555_5555_5555_5555;
+    const K2: BitBoard = 0x3333_3333_3333_3333;
+    const K4: BitBoard = 0x0f0f_0f0f_0f0f_0f0f;
+    let bits = ((bits >> 1) & K1) | ((bits & K1) << 1);
+    let bits = ((bits >> 2) & K2) | ((bits & K2) << 2);
+    ((bits >> 4) & K4) | ((bits & K4) << 4)
+}
+
+// x と y を入れ替える
+fn flip_diagonal(bits: BitBoard) -> BitBoard {
+    const K1: BitBoard = 0x5500_5500_5500_5500;
+    const K2: BitBoard = 0x3333_0000_3333_0000;
+    const K4: BitBoard = 0x0f0f_0f0f_0000_0000;
+    let mut bits = bits;
+    let t = K4 & (bits ^ (bits << 28));
+    bits ^= t ^ (t >> 28);
+    let t = K2 & (bits ^ (bits << 14));
+    bits ^= t ^ (t >> 14);
+    let t = K1 & (bits ^ (bits << 7));
+    bits ^= t ^ (t >> 7);
+    bits
+}
+
+impl Coordinate {
+    pub fn transform(self, symmetry: Symmetry) -> Self {
+        let Coordinate { x, y } = self;
+        match symmetry {
+            Symmetry::Identity => self,
+            Symmetry::Rotate90 => self.right_rotate(),
+            Symmetry::Rotate180 => Coordinate { x: 7 - x, y: 7 - y },
+            Symmetry::Rotate270 => Coordinate { x: y, y: 7 - x },
+            Symmetry::FlipHorizontal => Coordinate { x: 7 - x, y },
+            Symmetry::FlipVertical => Coordinate { x, y: 7 - y },
+            Symmetry::FlipDiagonal => Coordinate { x: y, y: x },
+            Symmetry::FlipAntiDiagonal => Coordinate { x: 7 - y, y: 7 - x },
+        }
+    }
+}
+
+impl Board {
+    pub fn transform(&self, symmetry: Symmetry) -> Self {
+        Board::from_bits(
+            symmetry.apply_bits(self.black()),
+            symmetry.apply_bits(self.white()),
+        )
+    }
+
+    // 8 通りの変換のうち (黒, 白) のビットボードが最小になるものを代表として返す
+    // 返す Symmetry は self を代表に移す変換
+    pub fn canonical(&self) -> (Self, Symmetry) {
+        Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| (self.transform(symmetry), symmetry))
+            .min_by_key(|(board, _)| (board.black(), board.white()))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::types::Color;
+
+    fn all_coordinates() -> impl Iterator<Item = Coordinate> {
+        (0..64).map(Coordinate::from_index)
+    }
+
+    // 対称性のない局面
+    fn sample_board() -> Board {
+        let mut board = Board::new();
+        for (coord, turn) in [
+            ("f5", Color::Black),
+            ("f6", Color::White),
+            ("e6", Color::Black),
+            ("f4", Color::White),
+            ("e3", Color::Black),
+        ] {
+            board.move_piece(coord.parse().unwrap(), turn).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn apply_bits_matches_coordinate_transform() {
+        for symmetry in Symmetry::ALL {
+            for coord in all_coordinates() {
+                assert_eq!(
+                    symmetry.apply_bits(coord.to_bit()),
+                    coord.transform(symmetry).to_bit(),
+                    "{symmetry:?} {coord}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let board = sample_board();
+        for symmetry in Symmetry::ALL {
+            for coord in all_coordinates() {
+                assert_eq!(
+                    coord.transform(symmetry).transform(symmetry.inverse()),
+                    coord
+                );
+            }
+            assert_eq!(
+                board.transform(symmetry).transform(symmetry.inverse()),
+                board
+            );
+        }
+    }
+
+    #[test]
+    fn canonical_is_shared_by_all_images() {
+        let board = sample_board();
+        let (canonical, _) = board.canonical();
+        for symmetry in Symmetry::ALL {
+            let image = board.transform(symmetry);
+            let (image_canonical, to_canonical) = image.canonical();
+            assert_eq!(image_canonical, canonical, "{symmetry:?}");
+            assert_eq!(image.transform(to_canonical), canonical);
+        }
+        // 対称性のない局面の 8 つの像はすべて異なる
+        let images: HashSet<_> = Symmetry::ALL
+            .into_iter()
+            .map(|symmetry| board.transform(symmetry))
+            .collect();
+        assert_eq!(images.len(), 8);
+    }
+
+    #[test]
+    fn rotate90_agrees_with_right_rotate() {
+        for coord in all_coordinates() {
+            assert_eq!(coord.transform(Symmetry::Rotate90), coord.right_rotate());
+            assert_eq!(
+                Symmetry::Rotate90.apply_bits(coord.to_bit()),
+                coord.right_rotate().to_bit()
+            );
+        }
+    }
+}
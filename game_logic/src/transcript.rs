@@ -0,0 +1,189 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    board::Board,
+    error::NotationError,
+    game_state::GameState,
+    types::{Color, Coordinate, GameStatus, Move},
+};
+
+// "f5d6c3..." 形式の棋譜。パスは省略して保持する
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Transcript {
+    moves: Vec<Coordinate>,
+}
+
+impl Transcript {
+    pub fn moves(&self) -> &[Coordinate] {
+        &self.moves
+    }
+
+    // 初期局面から棋譜を再生する
+    pub fn replay(&self) -> Result<GameState, NotationError> {
+        let moves: Vec<Move> = self.moves.iter().map(|&coord| Move::Put(coord)).collect();
+        replay_moves(&moves)
+    }
+}
+
+fn replay_moves(moves: &[Move]) -> Result<GameState, NotationError> {
+    let mut state = GameState::new();
+    state.status = GameStatus::InProgress;
+    // 直前の着手で相手に強制パスが発生し、まだ棋譜上のパスと対応付けていない
+    let mut pending_pass = false;
+
+    for (index, &action) in moves.iter().enumerate() {
+        match action {
+            Move::Pass => {
+                if !pending_pass {
+                    return Err(NotationError::IllegalPass { index });
+                }
+                pending_pass = false;
+            }
+            Move::Put(coord) => {
+                state
+                    .make_move(coord)
+                    .map_err(|error| NotationError::IllegalMove { index, error })?;
+                pending_pass = state
+                    .history
+                    .last()
+                    .is_some_and(|record| record.action == Move::Pass);
+            }
+        }
+    }
+    Ok(state)
+}
+
+// 局面から始めた対局を初期局面からの棋譜にすると別の対局になるので、エラーにする
+impl TryFrom<&GameState> for Transcript {
+    type Error = NotationError;
+
+    fn try_from(state: &GameState) -> Result<Self, Self::Error> {
+        let first_turn = state
+            .history
+            .first()
+            .map_or(state.turn, |record| record.color);
+        if state.board_at(0) != Board::new() || first_turn != Color::Black {
+            return Err(NotationError::NotFromInitialPosition);
+        }
+        Ok(Transcript {
+            moves: state
+                .history
+                .iter()
+                .filter_map(|record| match record.action {
+                    Move::Put(coord) => Some(coord),
+                    Move::Pass => None,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for coord in &self.moves {
+            write!(f, "{}", coord)?;
+        }
+        Ok(())
+    }
+}
+
+// 空白は無視し、"pa" または "--" を明示的なパスとして受け付ける
+impl FromStr for Transcript {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let moves = chars
+            .chunks(2)
+            .map(|token| {
+                let token: String = token.iter().collect();
+                if token.eq_ignore_ascii_case("pa") || token == "--" {
+                    Ok(Move::Pass)
+                } else {
+                    token.parse().map(Move::Put)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        replay_moves(&moves)?;
+        Ok(Transcript {
+            moves: moves
+                .into_iter()
+                .filter_map(|action| match action {
+                    Move::Put(coord) => Some(coord),
+                    Move::Pass => None,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 8 手目の h6 の後、黒は打てる場所がなくパスする
+    const WITH_PASS: &str = "f5f6c4g5h5h4f7h6";
+
+    #[test]
+    fn display_round_trips() {
+        for text in ["", "f5", "f5d6c3d3c4f4f6f3e6e7", WITH_PASS] {
+            let transcript: Transcript = text.parse().unwrap();
+            assert_eq!(transcript.to_string(), text);
+            let state = transcript.replay().unwrap();
+            assert_eq!(Transcript::try_from(&state), Ok(transcript));
+        }
+        // 大文字と空白も受け付ける
+        let transcript: Transcript = "F5 d6\nC3".parse().unwrap();
+        assert_eq!(transcript.to_string(), "f5d6c3");
+    }
+
+    #[test]
+    fn accepts_explicit_passes() {
+        let state = WITH_PASS.parse::<Transcript>().unwrap().replay().unwrap();
+        assert_eq!(state.history.last().unwrap().action, Move::Pass);
+        assert_eq!(state.turn, Color::White);
+
+        for marker in ["pa", "PA", "--"] {
+            let transcript: Transcript = format!("{WITH_PASS}{marker}").parse().unwrap();
+            assert_eq!(transcript.to_string(), WITH_PASS);
+        }
+    }
+
+    #[test]
+    fn rejects_illegal_passes_and_moves() {
+        assert_eq!(
+            "f5pa".parse::<Transcript>(),
+            Err(NotationError::IllegalPass { index: 1 })
+        );
+        assert_eq!(
+            format!("{WITH_PASS}papa").parse::<Transcript>(),
+            Err(NotationError::IllegalPass { index: 9 })
+        );
+        assert!(matches!(
+            "f5f5".parse::<Transcript>(),
+            Err(NotationError::IllegalMove { index: 1, .. })
+        ));
+        assert!(matches!(
+            "f5z9".parse::<Transcript>(),
+            Err(NotationError::InvalidCoordinate(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_games_not_from_the_initial_position() {
+        let mut state = GameState::from_position(
+            "---------------------------OX------XO--------------------------- O",
+        )
+        .unwrap();
+        assert_eq!(
+            Transcript::try_from(&state),
+            Err(NotationError::NotFromInitialPosition)
+        );
+        state.make_move(state.valid_moves()[0]).unwrap();
+        assert_eq!(
+            Transcript::try_from(&state),
+            Err(NotationError::NotFromInitialPosition)
+        );
+    }
+}
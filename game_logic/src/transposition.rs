@@ -0,0 +1,70 @@
+// Zobrist ハッシュをキーにした固定サイズの置換表。衝突したエントリは上書きする
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<T> {
+    entries: Vec<Option<(u64, T)>>,
+    mask: usize,
+}
+
+impl<T: Copy> TranspositionTable<T> {
+    // 2^bits 個のエントリを持つ表を作る
+    pub fn new(bits: u32) -> Self {
+        let size = 1 << bits;
+        TranspositionTable {
+            entries: vec![None; size],
+            mask: size - 1,
+        }
+    }
+
+    pub fn get(&self, key: u64) -> Option<T> {
+        match self.entries[key as usize & self.mask] {
+            Some((stored, value)) if stored == key => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, value: T) {
+        self.entries[key as usize & self.mask] = Some((key, value));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_get() {
+        let mut table = TranspositionTable::new(4);
+        assert_eq!(table.get(3), None);
+        table.insert(3, 'a');
+        table.insert(4, 'b');
+        assert_eq!(table.get(3), Some('a'));
+        assert_eq!(table.get(4), Some('b'));
+        // 同じキーは上書きする
+        table.insert(3, 'c');
+        assert_eq!(table.get(3), Some('c'));
+    }
+
+    #[test]
+    fn colliding_key_replaces_entry() {
+        let mut table = TranspositionTable::new(4);
+        // 下位 4 ビットが同じキーは同じ場所に入る
+        table.insert(0x15, 'a');
+        table.insert(0x25, 'b');
+        assert_eq!(table.get(0x15), None);
+        assert_eq!(table.get(0x25), Some('b'));
+    }
+
+    #[test]
+    fn clear_removes_all_entries() {
+        let mut table = TranspositionTable::new(4);
+        for key in 0..16 {
+            table.insert(key, key);
+        }
+        table.clear();
+        assert!((0..16).all(|key| table.get(key).is_none()));
+    }
+}
@@ -1,4 +1,12 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use std::{fmt, str::FromStr};
+
+use crate::{
+    alpha_beta_agent::AlphaBetaConfig, error::NotationError, monte_carlo::config::SearchConfig,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Color {
     Black,
     White,
@@ -14,12 +22,58 @@ impl Color {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum AgentType {
     Random,
-    MonteCarlo,
+    MonteCarlo(SearchConfig),
+    AlphaBeta(AlphaBetaConfig),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Player {
+    Human,
+    Agent(AgentType),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Players {
+    pub black: Player,
+    pub white: Player,
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        Players {
+            black: Player::Human,
+            white: Player::Human,
+        }
+    }
+}
+
+impl Players {
+    pub fn get(&self, color: Color) -> Player {
+        match color {
+            Color::Black => self.black,
+            Color::White => self.white,
+        }
+    }
+
+    // 先後を入れ替える
+    pub fn swapped(self) -> Self {
+        Players {
+            black: self.white,
+            white: self.black,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum GameStatus {
     BeforeStart,
     InProgress,
@@ -27,6 +81,14 @@ pub enum GameStatus {
     Draw,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Move {
+    Put(Coordinate),
+    Pass,
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Coordinate {
     pub x: u32,
@@ -36,6 +98,12 @@ impl Coordinate {
     pub fn to_bit(self) -> BitBoard {
         1 << (self.y * 8 + self.x)
     }
+    pub fn from_index(index: u32) -> Self {
+        Coordinate {
+            x: index % 8,
+            y: index / 8,
+        }
+    }
     pub fn right_rotate(self) -> Self {
         Coordinate {
             x: 7 - self.y,
@@ -50,4 +118,32 @@ impl From<(u32, u32)> for Coordinate {
     }
 }
 
+// "a1".."h8" 形式 (列が x、行が y)
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.x as u8) as char, self.y + 1)
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[column, row] => {
+                let column = column.to_ascii_lowercase();
+                if (b'a'..=b'h').contains(&column) && (b'1'..=b'8').contains(&row) {
+                    Ok(Coordinate {
+                        x: (column - b'a') as u32,
+                        y: (row - b'1') as u32,
+                    })
+                } else {
+                    Err(NotationError::InvalidCoordinate(s.to_string()))
+                }
+            }
+            _ => Err(NotationError::InvalidCoordinate(s.to_string())),
+        }
+    }
+}
+
 pub type BitBoard = u64;
@@ -0,0 +1,56 @@
+use crate::types::{BitBoard, Color};
+
+// 盤面ハッシュ用の乱数表。実行ごとに値が変わらないよう splitmix64 で固定の列を作る
+const fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const fn keys(seed: u64) -> [u64; 64] {
+    let mut keys = [0; 64];
+    let mut state = seed;
+    let mut i = 0;
+    while i < 64 {
+        state = splitmix64(state);
+        keys[i] = state;
+        i += 1;
+    }
+    keys
+}
+
+const BLACK_KEYS: [u64; 64] = keys(1);
+const WHITE_KEYS: [u64; 64] = keys(2);
+// 白番のときに XOR する値
+pub(crate) const WHITE_TO_MOVE: u64 = splitmix64(3);
+
+pub(crate) fn piece_key(index: u32, color: Color) -> u64 {
+    match color {
+        Color::Black => BLACK_KEYS[index as usize],
+        Color::White => WHITE_KEYS[index as usize],
+    }
+}
+
+// 石の色を反転させたときに XOR する値
+pub(crate) fn flip_key(flips: BitBoard) -> u64 {
+    let mut flips = flips;
+    let mut key = 0;
+    while flips != 0 {
+        let index = flips.trailing_zeros() as usize;
+        key ^= BLACK_KEYS[index] ^ WHITE_KEYS[index];
+        flips &= flips - 1;
+    }
+    key
+}
+
+pub(crate) fn hash(black: BitBoard, white: BitBoard) -> u64 {
+    let mut key = 0;
+    for (mut bits, keys) in [(black, &BLACK_KEYS), (white, &WHITE_KEYS)] {
+        while bits != 0 {
+            key ^= keys[bits.trailing_zeros() as usize];
+            bits &= bits - 1;
+        }
+    }
+    key
+}
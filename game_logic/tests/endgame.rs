@@ -0,0 +1,137 @@
+use game_logic::{
+    board::Board,
+    endgame::{solve, solve_moves},
+    rng::seeded_rng,
+    types::Color,
+};
+use rand::seq::IndexedRandom;
+
+// 局面文字列と、手番側から見た最終石差
+// 空きマスが 8 以下のものは下の brute_force でも確かめる
+const SOLVED_POSITIONS: [(&str, i32); 6] = [
+    // 終局済み (空きマスは勝った側に数える)
+    (
+        "XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXOOOOOOO- X",
+        50,
+    ),
+    // 手番側はパス
+    (
+        "OXXXXXX-OXXXXXXXOOOXX-O-OOXOXXXXOXOOOOOO-XXOXXXO-XXXXXXO--XXXX-O X",
+        -50,
+    ),
+    (
+        "-X--XXXOOOOOXXOO-OOOXOOOOOOOXOO-OOOOOOOXOOOOXX-OXXOXX---XO-OOOOO X",
+        6,
+    ),
+    (
+        "--XXXX-XOOOOOOOOX-OXXX-OXXOXXXOO--XOOXOO-XOOXOOOXXXXOOOX--XOOOOO X",
+        -8,
+    ),
+    (
+        "-XO--OOXOOX-XXXX-OOXXXOOXOOOXXO-X-OOOXXOXXXOOOOO-XOXOOOOX--XOO-X O",
+        -14,
+    ),
+    (
+        "X-OXO-XO-X-XO-O-XOXXXOXXXXXXXOOOOXXOXXOO-OXOXOXOXXOOOXXXX-XOX--- O",
+        8,
+    ),
+];
+
+#[test]
+fn solves_known_positions() {
+    for (position, disc_diff) in SOLVED_POSITIONS {
+        let (board, turn) = Board::from_position_string(position).unwrap();
+        let solution = solve(&board, turn);
+        assert_eq!(solution.disc_diff, disc_diff, "{}", position);
+
+        match solution.best_move {
+            Some(coord) => {
+                // 最善手を指した後の局面も同じ石差になる
+                let mut next = board;
+                next.move_piece(coord, turn).unwrap();
+                assert_eq!(-solve(&next, turn.opponent()).disc_diff, disc_diff);
+            }
+            None => assert_eq!(board.legal_moves(turn), 0),
+        }
+    }
+}
+
+// solve とは独立に、枝刈りも並べ替えもせずに全手順を読む
+fn brute_force(board: &Board, turn: Color) -> i32 {
+    let moves = board.valid_moves(turn);
+    if moves.is_empty() {
+        if board.valid_moves(turn.opponent()).is_empty() {
+            let (black, white) = board.count_pieces();
+            let diff = match turn {
+                Color::Black => black as i32 - white as i32,
+                Color::White => white as i32 - black as i32,
+            };
+            let empties = board.count_empties() as i32;
+            return diff + diff.signum() * empties;
+        }
+        return -brute_force(board, turn.opponent());
+    }
+    moves
+        .into_iter()
+        .map(|coord| {
+            let mut next = *board;
+            next.move_piece(coord, turn).unwrap();
+            -brute_force(&next, turn.opponent())
+        })
+        .max()
+        .unwrap()
+}
+
+// 初期局面から空きマスが empties 個になるまでランダムに打った局面
+fn random_position(seed: u64, empties: u32) -> Option<(Board, Color)> {
+    let mut rng = seeded_rng(Some(seed));
+    let mut board = Board::new();
+    let mut turn = Color::Black;
+    while board.count_empties() > empties {
+        let moves = board.valid_moves(turn);
+        match moves.choose(&mut rng) {
+            Some(&coord) => {
+                board.move_piece(coord, turn).unwrap();
+            }
+            None if board.legal_moves(turn.opponent()) == 0 => return None,
+            None => {}
+        }
+        turn = turn.opponent();
+    }
+    Some((board, turn))
+}
+
+#[test]
+fn known_positions_agree_with_brute_force() {
+    for (position, disc_diff) in SOLVED_POSITIONS {
+        let (board, turn) = Board::from_position_string(position).unwrap();
+        if board.count_empties() <= 8 {
+            assert_eq!(brute_force(&board, turn), disc_diff, "{}", position);
+        }
+    }
+}
+
+#[test]
+fn random_positions_agree_with_brute_force() {
+    let positions: Vec<_> = (0..30)
+        .filter_map(|seed| random_position(seed, 5 + seed as u32 % 5))
+        .collect();
+    assert!(positions.len() >= 20);
+    for (board, turn) in positions {
+        let position = board.to_position_string(turn);
+        let expected = brute_force(&board, turn);
+        assert_eq!(solve(&board, turn).disc_diff, expected, "{}", position);
+
+        for (coord, score) in solve_moves(&board, turn) {
+            let mut next = board;
+            next.move_piece(coord, turn).unwrap();
+            assert_eq!(
+                score,
+                -brute_force(&next, turn.opponent()),
+                "{} {}",
+                position,
+                coord
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use game_logic::{
+    alpha_beta_agent::AlphaBetaConfig,
+    game_controller::GameController,
+    game_state::GameState,
+    monte_carlo::config::SearchConfig,
+    transcript::Transcript,
+    types::{AgentType, GameStatus, Player, Players},
+};
+
+// 時間制限があると反復回数が変わるので、反復回数だけで打ち切る設定を使う
+fn play(black: AgentType, white: AgentType, seed: u64) -> String {
+    let mut state = GameState::new();
+    state.players = Players {
+        black: Player::Agent(black),
+        white: Player::Agent(white),
+    };
+    state.status = GameStatus::InProgress;
+    let mut controller = GameController::with_seed(state, Some(seed));
+    controller.run().unwrap();
+    Transcript::try_from(&controller.state).unwrap().to_string()
+}
+
+#[test]
+fn same_seed_replays_the_same_game() {
+    let mcts = AgentType::MonteCarlo(SearchConfig::easy());
+    // 定石手はランダムに選ぶので、シードで決まることを確かめる
+    let alpha_beta = AgentType::AlphaBeta(AlphaBetaConfig {
+        book: true,
+        ..AlphaBetaConfig::easy()
+    });
+    for (black, white) in [
+        (AgentType::Random, AgentType::Random),
+        (mcts, AgentType::Random),
+        (mcts, mcts),
+        (alpha_beta, mcts),
+        (AgentType::Random, alpha_beta),
+    ] {
+        assert_eq!(play(black, white, 42), play(black, white, 42));
+    }
+}
+
+#[test]
+fn different_seeds_play_different_games() {
+    let games: Vec<_> = (0..4)
+        .map(|seed| play(AgentType::Random, AgentType::Random, seed))
+        .collect();
+    assert!(games.iter().any(|game| *game != games[0]));
+}
+
+// 定石の中で手が分かれるので、シードが違えば別の対局になる
+#[test]
+fn book_moves_depend_on_the_seed() {
+    let alpha_beta = AgentType::AlphaBeta(AlphaBetaConfig {
+        book: true,
+        ..AlphaBetaConfig::easy()
+    });
+    let games: Vec<_> = (0..8)
+        .map(|seed| play(alpha_beta, alpha_beta, seed))
+        .collect();
+    assert!(games.iter().any(|game| *game != games[0]));
+}
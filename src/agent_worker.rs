@@ -0,0 +1,174 @@
+use game_logic::{
+    ai_agent::{initialize_agent, AiAgent},
+    board::Board,
+    endgame::solve_moves,
+    monte_carlo::{config::SearchConfig, runner::analyze},
+    rng::{color_seed, seeded_rng},
+    types::{AgentType, Color, Coordinate},
+};
+use gloo_worker::{HandlerId, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AgentRequest {
+    // Board::to_position_string の形式 (手番を含む)
+    pub position: String,
+    pub agent: AgentType,
+    // 対局のシード。同じシードと着手の列なら同じ手を返す
+    pub seed: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AgentResponse {
+    pub position: String,
+    // "f5" 形式
+    pub coord: String,
+}
+
+// ヒントでは空きマスがこの数以下なら石差を読み切る
+const HINT_ENDGAME_EMPTIES: u32 = 10;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct HintRequest {
+    pub position: String,
+    pub seed: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub enum HintValue {
+    // 手番側の推定勝率 (0.0 - 1.0)
+    WinRate(f64),
+    // 読み切った最終石差
+    DiscDiff(i32),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+pub struct Hint {
+    pub coord: Coordinate,
+    pub value: HintValue,
+    pub best: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct HintResponse {
+    pub position: String,
+    pub hints: Vec<Hint>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum AgentInput {
+    Move(AgentRequest),
+    Hint(HintRequest),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum AgentOutput {
+    Move(AgentResponse),
+    Hint(HintResponse),
+}
+
+// 各合法手の評価。終盤は読み切りの石差、それ以外はモンテカルロ木探索の勝率を返す
+fn hints(board: &Board, color: Color, seed: u64) -> Vec<Hint> {
+    let mut hints: Vec<Hint> = if board.count_empties() <= HINT_ENDGAME_EMPTIES {
+        solve_moves(board, color)
+            .into_iter()
+            .map(|(coord, disc_diff)| Hint {
+                coord,
+                value: HintValue::DiscDiff(disc_diff),
+                best: false,
+            })
+            .collect()
+    } else {
+        analyze(
+            board,
+            &color,
+            &SearchConfig::normal(),
+            &mut seeded_rng(Some(seed)),
+        )
+        .into_iter()
+        .map(|stats| Hint {
+            coord: stats.coord,
+            value: HintValue::WinRate(stats.win_rate()),
+            best: false,
+        })
+        .collect()
+    };
+    let score = |hint: &Hint| match hint.value {
+        HintValue::WinRate(win_rate) => win_rate,
+        HintValue::DiscDiff(disc_diff) => disc_diff as f64,
+    };
+    let best = hints.iter().map(score).fold(f64::NEG_INFINITY, f64::max);
+    for hint in &mut hints {
+        hint.best = score(hint) == best;
+    }
+    hints
+}
+
+// 探索木を再利用できるよう、エージェントは色ごとにワーカー内で保持し続ける
+pub struct AgentWorker {
+    black_agent: Option<Box<dyn AiAgent>>,
+    white_agent: Option<Box<dyn AiAgent>>,
+    // 今のエージェントを作ったときの対局のシード
+    seed: Option<u64>,
+}
+
+impl AgentWorker {
+    fn next_move(&mut self, request: AgentRequest) -> Option<AgentResponse> {
+        let (board, color) = Board::from_position_string(&request.position).ok()?;
+        // 別の対局になったらエージェントを作り直す
+        if self.seed != Some(request.seed) {
+            self.black_agent = None;
+            self.white_agent = None;
+            self.seed = Some(request.seed);
+        }
+        let slot = match color {
+            Color::Black => &mut self.black_agent,
+            Color::White => &mut self.white_agent,
+        };
+        let agent = match slot.take() {
+            Some(agent) if agent.agent_type() == request.agent => agent,
+            _ => initialize_agent(request.agent, color, Some(color_seed(request.seed, color))),
+        };
+        let coord = agent.next_move(&board);
+        *slot = Some(agent);
+
+        Some(AgentResponse {
+            position: request.position,
+            coord: coord.to_string(),
+        })
+    }
+}
+
+impl Worker for AgentWorker {
+    type Message = ();
+    type Input = AgentInput;
+    type Output = AgentOutput;
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        AgentWorker {
+            black_agent: None,
+            white_agent: None,
+            seed: None,
+        }
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, input: Self::Input, id: HandlerId) {
+        let output =
+            match input {
+                AgentInput::Move(request) => self.next_move(request).map(AgentOutput::Move),
+                AgentInput::Hint(request) => Board::from_position_string(&request.position)
+                    .ok()
+                    .map(|(board, color)| {
+                        AgentOutput::Hint(HintResponse {
+                            hints: hints(&board, color, request.seed),
+                            position: request.position,
+                        })
+                    }),
+            };
+        if let Some(output) = output {
+            scope.respond(id, output);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use gloo_worker::Registrable;
+use yew_reversi::agent_worker::AgentWorker;
+
+fn main() {
+    console_error_panic_hook::set_once();
+    AgentWorker::registrar().register();
+}
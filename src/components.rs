@@ -1,3 +1,5 @@
+pub mod info_header;
+pub mod move_history;
 pub mod reversi_cell;
 pub mod reversi_table;
 pub mod setting_header;
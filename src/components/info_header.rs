@@ -1,110 +1,174 @@
 use yew::prelude::*;
 
 use crate::{
-    game_logic::types::Color,
-    setting_context::{SettingAction, SettingContext},
+    game_state_context::{BoardAction, GameStateContext},
+    share,
 };
+use game_logic::types::{Color, GameStatus, Move, Player};
 
-#[derive(PartialEq, Clone, Copy)]
-pub enum TurnColor {
-    Black,
-    White,
-    Random,
+#[derive(Properties, PartialEq)]
+pub struct InfoHeaderProps {}
+
+pub struct InfoHeader {
+    game_state: GameStateContext,
+    _context_listener: ContextHandle<GameStateContext>,
+    // リンクをコピーした直後は true
+    copied: bool,
 }
 
-pub enum SettingHeaderMessage {
-    SetColor(TurnColor),
-    Start,
+pub enum InfoHeaderMessage {
+    Update(GameStateContext),
+    Dispatch(BoardAction),
+    CopyLink,
 }
 
-pub struct SettingHeader {
-    selected_color: Option<TurnColor>,
-    show: bool,
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black => "黒",
+        Color::White => "白",
+    }
 }
 
-impl Component for SettingHeader {
-    type Message = SettingHeaderMessage;
-    type Properties = ();
+fn player_name(player: Player) -> &'static str {
+    match player {
+        Player::Human => "人間",
+        Player::Agent(_) => "AI",
+    }
+}
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        SettingHeader {
-            selected_color: None,
-            show: true,
+fn button(ctx: &Context<InfoHeader>, label: &'static str, action: fn() -> BoardAction) -> Html {
+    html! {
+        <button
+            class={classes!("px-4", "py-2", "mr-2", "rounded", "hover:bg-blue-600", "text-white", "bg-blue-500")}
+            onclick={ctx.link().callback(move |_| InfoHeaderMessage::Dispatch(action()))}
+        >
+            { label }
+        </button>
+    }
+}
+
+impl InfoHeader {
+    fn copy_link_button(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <button
+                class={classes!("px-4", "py-2", "mr-2", "rounded", "hover:bg-gray-300", "bg-gray-200")}
+                onclick={ctx.link().callback(|_| InfoHeaderMessage::CopyLink)}
+            >
+                { if self.copied { "コピーしました" } else { "リンクをコピー" } }
+            </button>
         }
     }
 
-    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn undo_redo_buttons(&self, ctx: &Context<Self>) -> Html {
+        let button = |label: &'static str, enabled: bool, action: fn() -> BoardAction| {
+            html! {
+                <button
+                    class={classes!("px-4", "py-2", "mr-2", "rounded", "bg-gray-200", (!enabled).then_some("opacity-50 cursor-not-allowed"))}
+                    onclick={ctx.link().callback(move |_| InfoHeaderMessage::Dispatch(action()))}
+                    disabled={!enabled}
+                >
+                    { label }
+                </button>
+            }
+        };
+        html! {
+            <>
+                { button("待った", self.game_state.can_undo(), || BoardAction::Undo) }
+                { button("やり直し", self.game_state.can_redo(), || BoardAction::Redo) }
+            </>
+        }
+    }
+}
+
+impl Component for InfoHeader {
+    type Message = InfoHeaderMessage;
+    type Properties = InfoHeaderProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (game_state, context_listener) = ctx
+            .link()
+            .context::<GameStateContext>(ctx.link().callback(InfoHeaderMessage::Update))
+            .unwrap();
+        InfoHeader {
+            game_state,
+            _context_listener: context_listener,
+            copied: false,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            SettingHeaderMessage::SetColor(color) => {
-                self.selected_color = Some(color);
+            InfoHeaderMessage::Update(game_state) => {
+                self.game_state = game_state;
+                self.copied = false;
+                true
             }
-            SettingHeaderMessage::Start => {
-                if let Some(color) = self.selected_color {
-                    let (setting_context, _) = ctx
-                        .link()
-                        .context::<SettingContext>(Callback::noop())
-                        .unwrap();
-                    setting_context.dispatch(SettingAction::SetPlayerColor(match color {
-                        TurnColor::Black => Some(Color::Black),
-                        TurnColor::White => Some(Color::White),
-                        TurnColor::Random => None,
-                    }));
-                    self.show = false;
-                }
+            InfoHeaderMessage::CopyLink => {
+                share::copy_link(&self.game_state.inner);
+                self.copied = true;
+                true
+            }
+            InfoHeaderMessage::Dispatch(action) => {
+                self.game_state.dispatch(action);
+                false
             }
         }
-        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        if !self.show {
+        let inner = &self.game_state.inner;
+        if inner.status == GameStatus::BeforeStart {
             return html! {};
         }
 
-        let disabled = self.selected_color.is_none();
+        let (black_count, white_count) = inner.board.count_pieces();
+        let score = html! {
+            <div class="mb-2">
+                <span class="mr-4">{ format!("黒 ({}): {black_count}", player_name(inner.players.black)) }</span>
+                <span>{ format!("白 ({}): {white_count}", player_name(inner.players.white)) }</span>
+            </div>
+        };
 
-        html! {
-            <div class="p-4 rounded">
-                <h2 class="text-lg font-bold mb-2">{ "設定" }</h2>
+        let result = match inner.status {
+            GameStatus::Winner(color) => Some(format!("{}の勝ち", color_name(color))),
+            GameStatus::Draw => Some("引き分け".to_string()),
+            GameStatus::BeforeStart | GameStatus::InProgress => None,
+        };
+        if let Some(result) = result {
+            return html! {
                 <div class="mb-4">
-                    <label class="mr-4">
-                        <input
-                            type="radio"
-                            name="turn"
-                            value="black"
-                            checked={self.selected_color == Some(TurnColor::Black)}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::Black))}
-                        />
-                        { "黒" }
-                    </label>
-                    <label class="mr-4">
-                        <input
-                            type="radio"
-                            name="turn"
-                            value="white"
-                            checked={self.selected_color == Some(TurnColor::White)}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::White))}
-                        />
-                        { "白" }
-                    </label>
-                    <label>
-                        <input
-                            type="radio"
-                            name="turn"
-                            value="random"
-                            checked={self.selected_color == Some(TurnColor::Random)}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::Random))}
-                        />
-                        { "ランダム" }
-                    </label>
+                    { score }
+                    <div class="p-4 mb-2 rounded bg-yellow-100 font-bold">
+                        { format!("対局終了: {result} ({black_count} - {white_count})") }
+                    </div>
+                    { button(ctx, "もう一度遊ぶ", || BoardAction::Restart) }
+                    { button(ctx, "先後を入れ替えて再戦", || BoardAction::Rematch) }
+                    { button(ctx, "新しい対局", || BoardAction::NewGame) }
+                    { self.undo_redo_buttons(ctx) }
+                    { self.copy_link_button(ctx) }
+                </div>
+            };
+        }
+
+        // 直前の記録がパスなら、その色は打てる場所がなかった
+        let pass = inner
+            .history
+            .last()
+            .and_then(|record| (record.action == Move::Pass).then_some(record.color));
+        html! {
+            <div class="mb-4">
+                { score }
+                <div>{ format!("{}の番です", color_name(inner.turn)) }</div>
+                if let Some(color) = pass {
+                    <div class="text-red-600">
+                        { format!("{}は打てる場所がないためパスしました", color_name(color)) }
+                    </div>
+                }
+                <div class="mt-2">
+                    { button(ctx, "新しい対局", || BoardAction::NewGame) }
+                    { self.undo_redo_buttons(ctx) }
+                    { self.copy_link_button(ctx) }
                 </div>
-                <button
-                    class={classes!("px-4", "py-2", "rounded", "hover:bg-blue-600", "text-white", "bg-blue-500", disabled.then_some("opacity-50 cursor-not-allowed hover:bg-blue-500"))}
-                    onclick={ctx.link().callback(|_| SettingHeaderMessage::Start)}
-                    {disabled}
-                >
-                    { "開始" }
-                </button>
             </div>
         }
     }
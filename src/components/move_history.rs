@@ -0,0 +1,125 @@
+use yew::prelude::*;
+
+use crate::game_state_context::{BoardAction, GameStateContext};
+use game_logic::types::{Color, Move};
+
+#[derive(Properties, PartialEq)]
+pub struct MoveHistoryProps {}
+
+pub struct MoveHistory {
+    game_state: GameStateContext,
+    _context_listener: ContextHandle<GameStateContext>,
+}
+
+pub enum MoveHistoryMessage {
+    Update(GameStateContext),
+}
+
+struct Entry {
+    label: String,
+    black_count: u32,
+    white_count: u32,
+}
+
+impl MoveHistory {
+    // 開始局面と、各着手後の局面の表示内容
+    fn entries(&self) -> Vec<Entry> {
+        let inner = &self.game_state.inner;
+        let mut board = inner.board_at(0);
+        let (black_count, white_count) = board.count_pieces();
+        let mut entries = vec![Entry {
+            label: "開始局面".to_string(),
+            black_count,
+            white_count,
+        }];
+        for (i, record) in inner.history.iter().enumerate() {
+            let color = match record.color {
+                Color::Black => "黒",
+                Color::White => "白",
+            };
+            let action = match record.action {
+                Move::Put(coord) => {
+                    board.make_move(coord, record.color, record.flips);
+                    coord.to_string()
+                }
+                Move::Pass => "パス".to_string(),
+            };
+            let (black_count, white_count) = board.count_pieces();
+            entries.push(Entry {
+                label: format!("{}. {color} {action}", i + 1),
+                black_count,
+                white_count,
+            });
+        }
+        entries
+    }
+
+    fn button(&self, label: &'static str, ply: Option<usize>, disabled: bool) -> Html {
+        let game_state = self.game_state.clone();
+        html! {
+            <button
+                class={classes!("px-2", "py-1", "mr-1", "rounded", "bg-gray-200", disabled.then_some("opacity-50 cursor-not-allowed"))}
+                onclick={Callback::from(move |_| game_state.dispatch(BoardAction::View(ply)))}
+                {disabled}
+            >
+                { label }
+            </button>
+        }
+    }
+}
+
+impl Component for MoveHistory {
+    type Message = MoveHistoryMessage;
+    type Properties = MoveHistoryProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (game_state, context_listener) = ctx
+            .link()
+            .context::<GameStateContext>(ctx.link().callback(MoveHistoryMessage::Update))
+            .unwrap();
+        MoveHistory {
+            game_state,
+            _context_listener: context_listener,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MoveHistoryMessage::Update(game_state) => {
+                self.game_state = game_state;
+                true
+            }
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let last = self.game_state.inner.history.len();
+        let current = self.game_state.viewing.unwrap_or(last);
+
+        html! {
+            <div class="ml-8">
+                <h2 class="text-lg font-bold mb-2">{ "棋譜" }</h2>
+                <div class="mb-2">
+                    { self.button("<<", Some(0), current == 0) }
+                    { self.button("<", Some(current.saturating_sub(1)), current == 0) }
+                    { self.button(">", Some(current + 1), current == last) }
+                    { self.button(">>", None, current == last) }
+                </div>
+                <ol class="h-80 overflow-y-auto">
+                    { for self.entries().into_iter().enumerate().map(|(ply, entry)| {
+                        let game_state = self.game_state.clone();
+                        html! {
+                            <li
+                                key={ply}
+                                class={classes!("px-2", "cursor-pointer", "hover:bg-gray-100", (ply == current).then_some("bg-gray-200"))}
+                                onclick={Callback::from(move |_| game_state.dispatch(BoardAction::View(Some(ply))))}
+                            >
+                                { format!("{} (黒 {} - 白 {})", entry.label, entry.black_count, entry.white_count) }
+                            </li>
+                        }
+                    }) }
+                </ol>
+            </div>
+        }
+    }
+}
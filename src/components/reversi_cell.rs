@@ -3,6 +3,7 @@ use yew::prelude::*;
 use game_logic::types::Color;
 
 use crate::game_state_context::{BoardAction, GameStateContext};
+use yew_reversi::agent_worker::{Hint, HintValue};
 
 #[derive(Properties, PartialEq)]
 pub struct ReversiCellProps {
@@ -10,6 +11,8 @@ pub struct ReversiCellProps {
     pub x: u32,
     pub y: u32,
     pub is_valid_move: bool,
+    #[prop_or_default]
+    pub hint: Option<Hint>,
 }
 
 pub struct ReversiCell;
@@ -46,7 +49,20 @@ impl Component for ReversiCell {
                 "
                 />
             },
-            None => html! {},
+            None => match ctx.props().hint {
+                Some(hint) => {
+                    let text = match hint.value {
+                        HintValue::WinRate(win_rate) => format!("{:.0}%", win_rate * 100.0),
+                        HintValue::DiscDiff(disc_diff) => format!("{disc_diff:+}"),
+                    };
+                    html! {
+                        <span class={classes!("text-xs", if hint.best { "font-bold text-yellow-300" } else { "text-white" })}>
+                            { text }
+                        </span>
+                    }
+                }
+                None => html! {},
+            },
         };
 
         let (game_state_context, _) = ctx
@@ -1,5 +1,7 @@
-use crate::{components::reversi_cell::ReversiCell, game_state_context::GameStateContext};
-use game_logic::types::GameStatus;
+use crate::{
+    components::reversi_cell::ReversiCell,
+    game_state_context::{BoardAction, GameStateContext},
+};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -40,18 +42,38 @@ impl Component for ReversiTable {
 
     fn view(&self, _ctx: &Context<Self>) -> Html {
         let valid_moves = self.game_state.inner.valid_moves();
+        let hints = self.game_state.current_hints();
+        let set_hint = {
+            let game_state = self.game_state.clone();
+            let hint = !self.game_state.hint;
+            Callback::from(move |_| game_state.dispatch(BoardAction::SetHint(hint)))
+        };
         html! {
+            <>
+            <label class="block mb-2">
+                <input type="checkbox" checked={self.game_state.hint} onchange={set_hint} />
+                { "ヒントを表示" }
+            </label>
+            if self.game_state.hint_request().is_some() {
+                <p class="mb-2">{ "ヒントを計算中..." }</p>
+            }
+            if self.game_state.thinking {
+                <p class="mb-2">{ "AI が考え中..." }</p>
+            }
+            if let Some(ply) = self.game_state.viewing {
+                <p class="mb-2">{ format!("{ply} 手目の局面を表示中") }</p>
+            }
             <table style="border-collapse: collapse;">
                 <tbody>
-                    { for self.game_state.inner.board.as_array().iter().enumerate().map(|(y, row)| {
+                    { for self.game_state.displayed_board().as_array().iter().enumerate().map(|(y, row)| {
                             html! {
                                 <tr key={y}>
                                     {
                                         for row.iter().enumerate().map(|(x, &cell)| {
                                             html! {
                                                 <ReversiCell key={x} color={cell} x={x as u32} y={y as u32} is_valid_move={
-                                                    self.game_state.inner.status == GameStatus::InProgress &&  valid_moves.iter().any(|&coord| coord == (x as u32, y as u32).into())
-                                                }/>
+                                                    self.game_state.is_human_turn() &&  valid_moves.iter().any(|&coord| coord == (x as u32, y as u32).into())
+                                                } hint={hints.iter().find(|hint| hint.coord == (x as u32, y as u32).into()).copied()}/>
                                             }
                                         })
                                     }
@@ -60,6 +82,7 @@ impl Component for ReversiTable {
                         }) }
                 </tbody>
             </table>
+            </>
         }
     }
 }
@@ -1,104 +1,262 @@
 use yew::prelude::*;
 
 use crate::game_state_context::{BoardAction, GameStateContext};
-use game_logic::types::Color;
+use game_logic::{
+    alpha_beta_agent::AlphaBetaConfig,
+    monte_carlo::config::SearchConfig,
+    types::{AgentType, Color, GameStatus, Player, Players},
+};
 
 #[derive(PartialEq, Clone, Copy)]
-pub enum TurnColor {
-    Black,
-    White,
+pub enum PlayerKind {
+    Human,
     Random,
+    MonteCarlo,
+    AlphaBeta,
+}
+
+impl PlayerKind {
+    const ALL: [PlayerKind; 4] = [
+        PlayerKind::Human,
+        PlayerKind::Random,
+        PlayerKind::MonteCarlo,
+        PlayerKind::AlphaBeta,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PlayerKind::Human => "人間",
+            PlayerKind::Random => "ランダム",
+            PlayerKind::MonteCarlo => "モンテカルロ",
+            PlayerKind::AlphaBeta => "アルファベータ",
+        }
+    }
+
+    // モンテカルロは定石で序盤に変化をつけ、アルファベータは同じ手を返すよう定石を使わない
+    fn default_book(self) -> bool {
+        self == PlayerKind::MonteCarlo
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "弱い",
+            Difficulty::Normal => "普通",
+            Difficulty::Hard => "強い",
+        }
+    }
+
+    fn search_config(self) -> SearchConfig {
+        match self {
+            Difficulty::Easy => SearchConfig::easy(),
+            Difficulty::Normal => SearchConfig::normal(),
+            Difficulty::Hard => SearchConfig::hard(),
+        }
+    }
+
+    fn alpha_beta_config(self) -> AlphaBetaConfig {
+        match self {
+            Difficulty::Easy => AlphaBetaConfig::easy(),
+            Difficulty::Normal => AlphaBetaConfig::normal(),
+            Difficulty::Hard => AlphaBetaConfig::hard(),
+        }
+    }
+}
+
+// 片方の色の設定
+#[derive(PartialEq, Clone, Copy)]
+pub struct PlayerSetting {
+    kind: PlayerKind,
+    difficulty: Difficulty,
+    book: bool,
+}
+
+impl PlayerSetting {
+    fn player(self) -> Player {
+        match self.kind {
+            PlayerKind::Human => Player::Human,
+            PlayerKind::Random => Player::Agent(AgentType::Random),
+            PlayerKind::MonteCarlo => Player::Agent(AgentType::MonteCarlo(SearchConfig {
+                book: self.book,
+                ..self.difficulty.search_config()
+            })),
+            PlayerKind::AlphaBeta => Player::Agent(AgentType::AlphaBeta(AlphaBetaConfig {
+                book: self.book,
+                ..self.difficulty.alpha_beta_config()
+            })),
+        }
+    }
 }
 
 pub enum SettingHeaderMessage {
-    SetColor(TurnColor),
+    SetKind(Color, PlayerKind),
+    SetDifficulty(Color, Difficulty),
+    SetBook(Color, bool),
+    SetShuffle(bool),
     Start,
+    Update(GameStateContext),
 }
 
 pub struct SettingHeader {
-    selected_color: TurnColor,
-    show: bool,
+    black: PlayerSetting,
+    white: PlayerSetting,
+    // 開始時に先後をランダムに入れ替える
+    shuffle: bool,
+    game_state: GameStateContext,
+    _context_listener: ContextHandle<GameStateContext>,
+}
+
+impl SettingHeader {
+    fn setting_mut(&mut self, color: Color) -> &mut PlayerSetting {
+        match color {
+            Color::Black => &mut self.black,
+            Color::White => &mut self.white,
+        }
+    }
+
+    fn player_view(&self, ctx: &Context<Self>, color: Color) -> Html {
+        let (setting, label, name) = match color {
+            Color::Black => (self.black, "黒", "black"),
+            Color::White => (self.white, "白", "white"),
+        };
+        let uses_engine = matches!(setting.kind, PlayerKind::MonteCarlo | PlayerKind::AlphaBeta);
+
+        html! {
+            <div class="mb-4">
+                <div>
+                    <span class="mr-2">{ label }</span>
+                    { for PlayerKind::ALL.into_iter().map(|kind| html! {
+                        <label class="mr-4">
+                            <input
+                                type="radio"
+                                name={format!("{name}-player")}
+                                checked={setting.kind == kind}
+                                onchange={ctx.link().callback(move |_| SettingHeaderMessage::SetKind(color, kind))}
+                            />
+                            { kind.label() }
+                        </label>
+                    }) }
+                </div>
+                if uses_engine {
+                    <div class="ml-6">
+                        <span class="mr-2">{ "強さ" }</span>
+                        { for Difficulty::ALL.into_iter().map(|difficulty| html! {
+                            <label class="mr-4">
+                                <input
+                                    type="radio"
+                                    name={format!("{name}-difficulty")}
+                                    checked={setting.difficulty == difficulty}
+                                    onchange={ctx.link().callback(move |_| SettingHeaderMessage::SetDifficulty(color, difficulty))}
+                                />
+                                { difficulty.label() }
+                            </label>
+                        }) }
+                        <label>
+                            <input
+                                type="checkbox"
+                                checked={setting.book}
+                                onchange={ctx.link().callback(move |_| SettingHeaderMessage::SetBook(color, !setting.book))}
+                            />
+                            { "定石を使う" }
+                        </label>
+                    </div>
+                }
+            </div>
+        }
+    }
 }
 
 impl Component for SettingHeader {
     type Message = SettingHeaderMessage;
     type Properties = ();
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let (game_state, context_listener) = ctx
+            .link()
+            .context::<GameStateContext>(ctx.link().callback(SettingHeaderMessage::Update))
+            .unwrap();
         SettingHeader {
-            selected_color: TurnColor::Random,
-            show: true,
+            black: PlayerSetting {
+                kind: PlayerKind::Human,
+                difficulty: Difficulty::Normal,
+                book: PlayerKind::Human.default_book(),
+            },
+            white: PlayerSetting {
+                kind: PlayerKind::MonteCarlo,
+                difficulty: Difficulty::Normal,
+                book: PlayerKind::MonteCarlo.default_book(),
+            },
+            shuffle: true,
+            game_state,
+            _context_listener: context_listener,
         }
     }
 
-    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            SettingHeaderMessage::SetColor(color) => {
-                self.selected_color = color;
+            SettingHeaderMessage::SetKind(color, kind) => {
+                let setting = self.setting_mut(color);
+                setting.kind = kind;
+                setting.book = kind.default_book();
+            }
+            SettingHeaderMessage::SetBook(color, book) => {
+                self.setting_mut(color).book = book;
+            }
+            SettingHeaderMessage::SetDifficulty(color, difficulty) => {
+                self.setting_mut(color).difficulty = difficulty;
+            }
+            SettingHeaderMessage::SetShuffle(shuffle) => {
+                self.shuffle = shuffle;
             }
             SettingHeaderMessage::Start => {
-                let (game_state_context, _) = ctx
-                    .link()
-                    .context::<GameStateContext>(Callback::noop())
-                    .unwrap();
-
-                let player_color = match self.selected_color {
-                    TurnColor::Black => Color::Black,
-                    TurnColor::White => Color::White,
-                    TurnColor::Random => {
-                        if rand::random() {
-                            Color::Black
-                        } else {
-                            Color::White
-                        }
-                    }
+                let players = Players {
+                    black: self.black.player(),
+                    white: self.white.player(),
+                };
+                let players = if self.shuffle && rand::random() {
+                    players.swapped()
+                } else {
+                    players
                 };
-                game_state_context.dispatch(BoardAction::StartGame(player_color));
-                self.show = false;
+                self.game_state.dispatch(BoardAction::StartGame(players));
+            }
+            SettingHeaderMessage::Update(game_state) => {
+                self.game_state = game_state;
             }
         }
         true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        if !self.show {
+        // 設定は対局前にだけ表示する。前回の設定はそのまま残す
+        if self.game_state.inner.status != GameStatus::BeforeStart {
             return html! {};
         }
 
+        let shuffle = !self.shuffle;
         html! {
             <div class="p-4 rounded">
                 <h2 class="text-lg font-bold mb-2">{ "設定" }</h2>
+                { self.player_view(ctx, Color::Black) }
+                { self.player_view(ctx, Color::White) }
                 <div class="mb-4">
-                    <span class="mr-2">{ "あなたの手番" }</span>
-                    <label class="mr-4">
-                        <input
-                            type="radio"
-                            name="turn"
-                            value="black"
-                            checked={self.selected_color == TurnColor::Black}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::Black))}
-                        />
-                        { "黒" }
-                    </label>
-                    <label class="mr-4">
-                        <input
-                            type="radio"
-                            name="turn"
-                            value="white"
-                            checked={self.selected_color == TurnColor::White}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::White))}
-                        />
-                        { "白" }
-                    </label>
                     <label>
                         <input
-                            type="radio"
-                            name="turn"
-                            value="random"
-                            checked={self.selected_color == TurnColor::Random}
-                            onchange={ctx.link().callback(|_| SettingHeaderMessage::SetColor(TurnColor::Random))}
+                            type="checkbox"
+                            checked={self.shuffle}
+                            onchange={ctx.link().callback(move |_| SettingHeaderMessage::SetShuffle(shuffle))}
                         />
-                        { "ランダム" }
+                        { "先後をランダムに決める" }
                     </label>
                 </div>
                 <button
@@ -1,27 +1,196 @@
+use crate::share;
 use game_logic::{
-    ai_agent::{initialize_agent, renew_agent},
+    board::Board,
+    error::GameError,
     game_state::GameState,
-    types::{AgentType, Color, Coordinate, GameStatus},
+    types::{Coordinate, GameStatus, Move, Player, Players},
 };
+use gloo_console as console;
+use gloo_storage::{LocalStorage, Storage};
+use gloo_worker::Spawnable;
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 use yew::prelude::*;
+use yew_reversi::agent_worker::{
+    AgentInput, AgentOutput, AgentRequest, AgentResponse, AgentWorker, Hint, HintRequest,
+    HintResponse,
+};
+
+const STORAGE_KEY: &str = "yew-reversi.game";
+
+// localStorage に保存する対局。game は share::encode の形式
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
+pub struct SavedGame {
+    players: Players,
+    game: String,
+    // 不具合を再現できるよう、AI の乱数のシードも残す
+    #[serde(default = "rand::random")]
+    seed: u64,
+}
 
 #[derive(PartialEq)]
 pub struct GameStateWrapper {
     pub inner: GameState,
+    // ワーカーが AI の手を計算している間は true
+    pub thinking: bool,
+    // 棋譜の途中の局面を表示しているときは、その手数 (None なら最新局面)
+    pub viewing: Option<usize>,
+    // ヒント表示が有効か
+    pub hint: bool,
+    // 直近に受け取ったヒント。局面が変わると使わなくなる
+    pub hints: Option<HintResponse>,
+    // AI とヒントの乱数のシード。対局を始めるたびに作り直す
+    pub seed: u64,
 }
 
 impl GameStateWrapper {
     pub fn new() -> Self {
         Self {
             inner: GameState::new(),
+            thinking: false,
+            viewing: None,
+            hint: false,
+            hints: None,
+            seed: rand::random(),
+        }
+    }
+
+    // 初期局面から対局を始めた状態
+    pub fn started(players: Players) -> Self {
+        let mut state = Self::new();
+        state.inner.players = players;
+        state.inner.status = GameStatus::InProgress;
+        state.update_thinking();
+        state
+    }
+
+    fn from_game(inner: GameState) -> Self {
+        let mut state = Self {
+            inner,
+            ..Self::new()
+        };
+        state.update_thinking();
+        state
+    }
+
+    // 共有リンクから開いた対局は、両者とも人間として再開する
+    pub fn from_location() -> Option<Self> {
+        share::take_from_location().map(Self::from_game)
+    }
+
+    // 保存された対局があれば復元する
+    pub fn load() -> Option<Self> {
+        let saved: SavedGame = LocalStorage::get(STORAGE_KEY).ok()?;
+        let mut inner = match share::decode(&saved.game) {
+            Ok(inner) => inner,
+            Err(err) => {
+                console::warn!(err.to_string());
+                return None;
+            }
+        };
+        inner.players = saved.players;
+        Some(Self {
+            seed: saved.seed,
+            ..Self::from_game(inner)
+        })
+    }
+
+    // 対局前なら None
+    pub fn saved_game(&self) -> Option<SavedGame> {
+        (self.inner.status != GameStatus::BeforeStart).then(|| SavedGame {
+            players: self.inner.players,
+            game: share::encode(&self.inner),
+            seed: self.seed,
+        })
+    }
+
+    pub fn is_human_turn(&self) -> bool {
+        self.viewing.is_none() && !self.thinking && self.inner.is_human_turn()
+    }
+
+    // 人間が指した手が残っていれば、その手番まで戻せる
+    pub fn can_undo(&self) -> bool {
+        self.inner.history.iter().any(|record| {
+            record.action != Move::Pass && self.inner.players.get(record.color) == Player::Human
+        })
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.inner.redo_history.is_empty()
+    }
+
+    // 盤面に表示する局面
+    pub fn displayed_board(&self) -> Board {
+        match self.viewing {
+            Some(ply) => self.inner.board_at(ply),
+            None => self.inner.board,
+        }
+    }
+
+    // AI の手番なら、ワーカーに送るリクエストを返す
+    pub fn agent_request(&self) -> Option<AgentRequest> {
+        match self.inner.current_player() {
+            Player::Agent(agent_type) if self.thinking => Some(AgentRequest {
+                position: self.inner.board.to_position_string(self.inner.turn),
+                agent: agent_type,
+                seed: self.seed,
+            }),
+            _ => None,
         }
     }
+
+    // ヒントを出す局面。人間の手番でヒントが有効なときだけ Some
+    fn hint_position(&self) -> Option<String> {
+        (self.hint && self.is_human_turn())
+            .then(|| self.inner.board.to_position_string(self.inner.turn))
+    }
+
+    // 現局面のヒントがまだなければ、ワーカーに送るリクエストを返す
+    pub fn hint_request(&self) -> Option<HintRequest> {
+        let position = self.hint_position()?;
+        if self
+            .hints
+            .as_ref()
+            .is_some_and(|hints| hints.position == position)
+        {
+            return None;
+        }
+        Some(HintRequest {
+            position,
+            seed: self.seed,
+        })
+    }
+
+    pub fn current_hints(&self) -> &[Hint] {
+        match (&self.hints, self.hint_position()) {
+            (Some(hints), Some(position)) if hints.position == position => &hints.hints,
+            _ => &[],
+        }
+    }
+
+    fn update_thinking(&mut self) {
+        self.thinking = self.inner.is_ai_turn();
+    }
 }
 
 pub enum BoardAction {
     Move(Coordinate),
-    StartGame(Color), // player color
+    StartGame(Players),
+    AgentMoved(AgentResponse),
+    HintsReady(HintResponse),
+    SetHint(bool),
+    // 同じ対局者で最初から指し直す
+    Restart,
+    // 先後を入れ替えて最初から指し直す
+    Rematch,
+    // 対局前に戻して設定をやり直す
+    NewGame,
+    // 棋譜の局面を表示する (None で最新局面に戻る)
+    View(Option<usize>),
+    // AI の手も含めて、人間の直前の手番まで戻す
+    Undo,
+    // Undo で戻した手を、次の人間の手番まで指し直す
+    Redo,
 }
 
 impl Reducible for GameStateWrapper {
@@ -29,28 +198,105 @@ impl Reducible for GameStateWrapper {
 
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         let mut state = GameStateWrapper {
-            inner: GameState {
-                board: self.inner.board,
-                turn_count: self.inner.turn_count,
-                turn: self.inner.turn,
-                status: self.inner.status,
-                ai_agent: renew_agent(&self.inner.ai_agent),
-            },
+            inner: self.inner.clone(),
+            thinking: self.thinking,
+            viewing: self.viewing,
+            hint: self.hint,
+            hints: self.hints.clone(),
+            seed: self.seed,
         };
         match action {
             BoardAction::Move(coord) => {
-                state.inner.make_move(coord).unwrap();
+                if !self.is_human_turn() {
+                    return self;
+                }
+                match state.inner.make_move(coord) {
+                    Ok(()) => {
+                        state.update_thinking();
+                        Rc::new(state)
+                    }
+                    // 不正な着手は無視して盤面をそのままにする
+                    Err(
+                        err @ (GameError::OccupiedSquare(_)
+                        | GameError::OutOfBounds(_)
+                        | GameError::NoFlips(_)
+                        | GameError::NotInProgress),
+                    ) => {
+                        console::warn!(err.to_string());
+                        self
+                    }
+                    Err(err @ (GameError::WrongTurn(_) | GameError::NoAgent)) => {
+                        console::error!(err.to_string());
+                        self
+                    }
+                }
+            }
+            BoardAction::StartGame(players) => Rc::new(GameStateWrapper {
+                hint: self.hint,
+                ..GameStateWrapper::started(players)
+            }),
+            BoardAction::AgentMoved(response) => {
+                // 計算中に局面が変わっていれば古い応答として捨てる
+                if !self.thinking
+                    || response.position != self.inner.board.to_position_string(self.inner.turn)
+                {
+                    return self;
+                }
+                let coord = match response.coord.parse::<Coordinate>() {
+                    Ok(coord) => coord,
+                    Err(err) => {
+                        console::error!(err.to_string());
+                        return self;
+                    }
+                };
+                if let Err(err) = state.inner.make_move(coord) {
+                    console::error!(err.to_string());
+                    return self;
+                }
+                state.update_thinking();
+                Rc::new(state)
+            }
+            BoardAction::HintsReady(response) => {
+                state.hints = Some(response);
+                Rc::new(state)
+            }
+            BoardAction::SetHint(hint) => {
+                state.hint = hint;
                 Rc::new(state)
             }
-            BoardAction::StartGame(player_color) => {
-                state.inner.status = GameStatus::InProgress;
-                state.inner.ai_agent = Some(initialize_agent(
-                    AgentType::MonteCarlo,
-                    player_color.opponent(),
-                ));
-                if player_color == Color::White {
-                    state.inner.make_agent_move().unwrap();
+            BoardAction::Restart => Rc::new(GameStateWrapper {
+                hint: self.hint,
+                ..GameStateWrapper::started(self.inner.players)
+            }),
+            BoardAction::Rematch => Rc::new(GameStateWrapper {
+                hint: self.hint,
+                ..GameStateWrapper::started(self.inner.players.swapped())
+            }),
+            BoardAction::NewGame => Rc::new(GameStateWrapper {
+                hint: self.hint,
+                ..GameStateWrapper::new()
+            }),
+            BoardAction::View(ply) => {
+                // 最新局面と同じ手数なら追従表示に戻す
+                state.viewing = ply.filter(|&ply| ply < self.inner.history.len());
+                Rc::new(state)
+            }
+            BoardAction::Undo => {
+                if !self.can_undo() {
+                    return self;
+                }
+                while state.inner.undo().is_some() && !state.inner.is_human_turn() {}
+                state.viewing = None;
+                state.update_thinking();
+                Rc::new(state)
+            }
+            BoardAction::Redo => {
+                if !self.can_redo() {
+                    return self;
                 }
+                while state.inner.redo().is_some() && !state.inner.is_human_turn() {}
+                state.viewing = None;
+                state.update_thinking();
                 Rc::new(state)
             }
         }
@@ -67,7 +313,53 @@ pub struct BoardProviderProps {
 
 #[function_component]
 pub fn GameStateProvider(props: &BoardProviderProps) -> Html {
-    let state = use_reducer(GameStateWrapper::new);
+    let state = use_reducer(|| {
+        GameStateWrapper::from_location()
+            .or_else(GameStateWrapper::load)
+            .unwrap_or_else(GameStateWrapper::new)
+    });
+
+    let bridge = {
+        let dispatcher = state.dispatcher();
+        use_memo((), move |_| {
+            AgentWorker::spawner()
+                .callback(move |output| {
+                    dispatcher.dispatch(match output {
+                        AgentOutput::Move(response) => BoardAction::AgentMoved(response),
+                        AgentOutput::Hint(response) => BoardAction::HintsReady(response),
+                    })
+                })
+                .spawn_with_loader("agent_worker_loader.js")
+        })
+    };
+
+    {
+        let bridge = bridge.clone();
+        use_effect_with(state.agent_request(), move |request| {
+            if let Some(request) = request {
+                bridge.send(AgentInput::Move(request.clone()));
+            }
+        });
+    }
+
+    use_effect_with(state.hint_request(), move |request| {
+        if let Some(request) = request {
+            bridge.send(AgentInput::Hint(request.clone()));
+        }
+    });
+
+    use_effect_with(state.saved_game(), |saved_game| {
+        let result = match saved_game {
+            Some(saved_game) => LocalStorage::set(STORAGE_KEY, saved_game),
+            None => {
+                LocalStorage::delete(STORAGE_KEY);
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            console::warn!(err.to_string());
+        }
+    });
 
     html! {
         <ContextProvider<GameStateContext> context={state}>
@@ -75,3 +367,60 @@ pub fn GameStateProvider(props: &BoardProviderProps) -> Html {
         </ContextProvider<GameStateContext>>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_logic::types::{AgentType, Color};
+
+    fn reduce<const N: usize>(
+        state: GameStateWrapper,
+        actions: [BoardAction; N],
+    ) -> Rc<GameStateWrapper> {
+        actions
+            .into_iter()
+            .fold(Rc::new(state), |state, action| state.reduce(action))
+    }
+
+    // AI の応手まで指した、人間 (黒) 対 AI (白) の対局
+    fn vs_agent() -> GameStateWrapper {
+        let mut state = GameStateWrapper::started(Players {
+            black: Player::Human,
+            white: Player::Agent(AgentType::Random),
+        });
+        for coord in ["f5", "d6", "c3", "d3"] {
+            state.inner.make_move(coord.parse().unwrap()).unwrap();
+        }
+        state.update_thinking();
+        state
+    }
+
+    #[test]
+    fn undo_rewinds_to_the_human_turn() {
+        let state = reduce(vs_agent(), [BoardAction::Undo]);
+        assert_eq!(state.inner.history.len(), 2);
+        assert_eq!(state.inner.turn, Color::Black);
+        assert!(state.is_human_turn());
+
+        let state = reduce(vs_agent(), [BoardAction::Undo, BoardAction::Undo]);
+        assert!(state.inner.history.is_empty());
+        assert!(!state.can_undo());
+
+        let state = reduce(
+            vs_agent(),
+            [BoardAction::Undo, BoardAction::Undo, BoardAction::Redo],
+        );
+        assert_eq!(state.inner.history.len(), 2);
+        assert!(state.is_human_turn());
+    }
+
+    #[test]
+    fn agents_alone_cannot_undo() {
+        let mut state = GameStateWrapper::started(Players {
+            black: Player::Agent(AgentType::Random),
+            white: Player::Agent(AgentType::Random),
+        });
+        state.inner.make_move("f5".parse().unwrap()).unwrap();
+        assert!(!state.can_undo());
+    }
+}
@@ -1,6 +1,10 @@
 mod components;
 mod game_state_context;
-use components::{reversi_table::ReversiTable, setting_header::SettingHeader};
+mod share;
+use components::{
+    info_header::InfoHeader, move_history::MoveHistory, reversi_table::ReversiTable,
+    setting_header::SettingHeader,
+};
 use game_state_context::GameStateProvider;
 use yew::prelude::*;
 
@@ -12,7 +16,11 @@ fn App() -> Html {
                 <div class={classes!("pb-4")}>
                     <SettingHeader />
                 </div>
-                <ReversiTable />
+                <InfoHeader />
+                <div class={classes!("flex")}>
+                    <ReversiTable />
+                    <MoveHistory />
+                </div>
             </GameStateProvider>
         </div>
     }
@@ -0,0 +1,60 @@
+use game_logic::{error::NotationError, game_state::GameState, transcript::Transcript};
+use gloo_console as console;
+use wasm_bindgen::JsValue;
+
+// 対局を "moves=f5d6..." か "position=<局面文字列>" の形式で表す
+// 初期局面から始まった対局は棋譜ごと、それ以外は現在の局面だけを残す
+pub fn encode(state: &GameState) -> String {
+    match Transcript::try_from(state) {
+        Ok(transcript) => format!("moves={transcript}"),
+        Err(_) => {
+            let position: String = state
+                .board
+                .to_position_string(state.turn)
+                .split_whitespace()
+                .collect();
+            format!("position={position}")
+        }
+    }
+}
+
+pub fn decode(s: &str) -> Result<GameState, NotationError> {
+    match s.split_once('=') {
+        Some(("moves", moves)) => moves.parse::<Transcript>()?.replay(),
+        Some(("position", position)) => GameState::from_position(position),
+        _ => Err(NotationError::InvalidPosition(s.to_string())),
+    }
+}
+
+// URL のハッシュに対局があれば読み込み、ハッシュは消しておく
+pub fn take_from_location() -> Option<GameState> {
+    let window = web_sys::window()?;
+    let hash = window.location().hash().ok()?;
+    let fragment = hash
+        .strip_prefix('#')
+        .filter(|fragment| !fragment.is_empty())?;
+    let state = match decode(fragment) {
+        Ok(state) => state,
+        Err(err) => {
+            console::warn!(err.to_string());
+            return None;
+        }
+    };
+    if let (Ok(history), Ok(pathname)) = (window.history(), window.location().pathname()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&pathname));
+    }
+    Some(state)
+}
+
+// 対局を開くためのリンクをクリップボードにコピーする
+pub fn copy_link(state: &GameState) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let Ok(href) = window.location().href() else {
+        return;
+    };
+    let base = href.split('#').next().unwrap_or_default();
+    let link = format!("{base}#{}", encode(state));
+    let _ = window.navigator().clipboard().write_text(&link);
+}